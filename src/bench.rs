@@ -0,0 +1,121 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::runner::{write_columns, Day, Part};
+use crate::{Error, Input, Result, Source};
+
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+pub struct Benchmark {
+    day: u8,
+    iterations: usize,
+    parse: Stats,
+    part1: Stats,
+    part2: Stats,
+}
+
+impl Benchmark {
+    // the input is read once; every iteration parses and solves it from scratch
+    pub fn run(day: &Day, iterations: usize, argument: Option<&str>) -> Result<Self> {
+        if iterations == 0 {
+            return Err(Error::validation("Benchmark needs at least one iteration"));
+        }
+
+        let input = Input::read(Source::resolve(day.number(), argument))?;
+        let mut samples = [Vec::new(), Vec::new(), Vec::new()];
+
+        for _ in 0..iterations {
+            // a failed part would only be timing its error
+            let answers = input.parse(|lines| day.solve(lines, &[Part::One, Part::Two]))?.checked()?;
+            let timings = answers.timings();
+
+            samples[0].push(timings.parse);
+            samples[1].extend(timings.part1);
+            samples[2].extend(timings.part2);
+        }
+
+        let [parse, part1, part2] = samples.map(|mut durations| Stats::new(&mut durations));
+
+        Ok(Self { day: day.number(), iterations, parse, part1, part2 })
+    }
+
+    pub fn parse(&self) -> Stats {
+        self.parse
+    }
+
+    pub fn part1(&self) -> Stats {
+        self.part1
+    }
+
+    pub fn part2(&self) -> Stats {
+        self.part2
+    }
+}
+
+impl fmt::Display for Benchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Day {:02} ({} iterations)", self.day, self.iterations)?;
+
+        let rows = [("parse", self.parse), ("part 1", self.part1), ("part 2", self.part2)]
+            .map(|(phase, stats)| {
+                [
+                    phase.to_string(),
+                    format!("{:.3?}", stats.median),
+                    format!("{:.3?}", stats.min),
+                    format!("{:.3?}", stats.max),
+                ]
+            });
+
+        write_columns(f, ["Phase", "Median", "Min", "Max"], &rows)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    // the median of an even number of samples is the mean of the middle two
+    fn new(durations: &mut [Duration]) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+
+        durations.sort();
+
+        let middle = durations.len() / 2;
+
+        let median = if durations.len().is_multiple_of(2) {
+            (durations[middle - 1] + durations[middle]) / 2
+        } else {
+            durations[middle]
+        };
+
+        Self { median, min: durations[0], max: durations[durations.len() - 1] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_durations() {
+        let mut odd = [5, 1, 3].map(Duration::from_millis);
+        let mut even = [4, 1, 2, 8].map(Duration::from_millis);
+
+        assert_eq!(
+            Stats::new(&mut odd),
+            Stats {
+                median: Duration::from_millis(3),
+                min: Duration::from_millis(1),
+                max: Duration::from_millis(5),
+            }
+        );
+
+        assert_eq!(Stats::new(&mut even).median, Duration::from_millis(3));
+    }
+}
@@ -0,0 +1,133 @@
+use std::env;
+use std::process::ExitCode;
+
+use advent_of_code_2025::bench::{Benchmark, DEFAULT_ITERATIONS};
+use advent_of_code_2025::runner::{Format, Selection, Table};
+
+const USAGE: &str = "\
+usage: aoc run <SELECTION> [--input <PATH | ->] [--format <text | json>]
+       aoc bench <SELECTION> [--iterations N] [--input <PATH | ->]
+
+SELECTION: all | DAY | START..END | START..=END
+           START..END leaves out END, so 3..9 runs days 3 to 8; use 3..=9 to include day 9";
+
+enum Command {
+    Run { format: Format },
+    Bench { iterations: usize },
+}
+
+struct Arguments {
+    command: Command,
+    selection: Selection,
+    input: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let arguments = match parse_args(&args) {
+        Ok(arguments) => arguments,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let succeeded = match arguments.command {
+        Command::Run { format } => run(&arguments, format),
+        Command::Bench { iterations } => bench(&arguments, iterations),
+    };
+
+    if !succeeded {
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn run(arguments: &Arguments, format: Format) -> bool {
+    let mut table = Table::new();
+
+    for day in arguments.selection.days() {
+        table.push(day.number(), day.run(arguments.input.as_deref()));
+    }
+
+    match format {
+        Format::Text => print!("{}", table),
+        Format::Json => {
+            print!("{}", table.json_lines());
+
+            for (heading, e) in table.errors() {
+                eprintln!("{}:\n{}", heading, e);
+            }
+        }
+    }
+
+    !table.has_errors()
+}
+
+fn bench(arguments: &Arguments, iterations: usize) -> bool {
+    let mut succeeded = true;
+
+    for (index, day) in arguments.selection.days().into_iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+
+        match Benchmark::run(day, iterations, arguments.input.as_deref()) {
+            Ok(benchmark) => print!("{}", benchmark),
+            Err(e) => {
+                println!("Day {:02}:\n{}", day.number(), e);
+                succeeded = false;
+            }
+        }
+    }
+
+    succeeded
+}
+
+fn parse_args(args: &[String]) -> Result<Arguments, String> {
+    let (command, selection, options) = match args {
+        [command, selection, options @ ..] if !selection.starts_with("--") => {
+            (command, selection, options)
+        }
+        [_, ..] => return Err("Missing day selection".to_string()),
+        [] => return Err("Missing command".to_string()),
+    };
+
+    let selection = Selection::try_from(selection.as_str()).map_err(|e| e.to_string())?;
+    let mut input = None;
+    let mut iterations = None;
+    let mut format = None;
+
+    for option in options.chunks(2) {
+        match option {
+            [flag, path] if flag == "--input" => input = Some(path.clone()),
+            [flag, count] if flag == "--iterations" => {
+                iterations = Some(
+                    count
+                        .parse::<usize>()
+                        .map_err(|e| format!("Invalid iteration count '{}': {}", count, e))?,
+                )
+            }
+            [flag, value] if flag == "--format" => {
+                format = Some(Format::try_from(value.as_str()).map_err(|e| e.to_string())?)
+            }
+            _ => return Err(format!("Unexpected arguments: {}", option.join(" "))),
+        }
+    }
+
+    let command = match command.as_str() {
+        "run" if iterations.is_some() => return Err("--iterations is only used by bench".to_string()),
+        "run" => Command::Run { format: format.unwrap_or(Format::Text) },
+        "bench" if format.is_some() => return Err("--format is only used by run".to_string()),
+        "bench" => Command::Bench { iterations: iterations.unwrap_or(DEFAULT_ITERATIONS) },
+        _ => return Err(format!("Unknown command: {}", command)),
+    };
+
+    if input.is_some() && selection.days().len() != 1 {
+        return Err("--input can only be used with a single day".to_string());
+    }
+
+    Ok(Arguments { command, selection, input })
+}
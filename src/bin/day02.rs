@@ -1,8 +1,9 @@
-use advent_of_code_2025::input_for_day;
-use advent_of_code_2025::day02::answer;
+use advent_of_code_2025::Input;
+use advent_of_code_2025::day02::{answer, Repetition};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(2)?;
-    println!("Answer: {}", answer(&input)?);
+    let input = Input::from_args(2)?;
+    println!("Part 1: {}", input.parse(|lines| answer(lines, Repetition::ExactlyTwice))?);
+    println!("Part 2: {}", input.parse(|lines| answer(lines, Repetition::AtLeastTwice))?);
     Ok(())
 }
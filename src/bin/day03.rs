@@ -1,9 +1,9 @@
-use advent_of_code_2025::input_for_day;
+use advent_of_code_2025::Input;
 use advent_of_code_2025::day03::BatteryBank;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(3)?;
-    let battery_banks = BatteryBank::parse_all(&input)?;
+    let input = Input::from_args(3)?;
+    let battery_banks = input.parse(|lines| BatteryBank::parse_all(lines))?;
 
     let sum: u128 = battery_banks
         .iter()
@@ -1,9 +1,9 @@
-use advent_of_code_2025::input_for_day;
+use advent_of_code_2025::Input;
 use advent_of_code_2025::day04::FactoryFloor;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(4)?;
-    let mut factory_floor = FactoryFloor::try_from(&input)?;
+    let input = Input::from_args(4)?;
+    let mut factory_floor = input.parse(|lines| FactoryFloor::try_from(lines))?;
 
     let original_roll_count = factory_floor.roll_count();
 
@@ -1,9 +1,9 @@
-use advent_of_code_2025::input_for_day;
+use advent_of_code_2025::Input;
 use advent_of_code_2025::day05::IngredientDatabase;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(5)?;
-    let ingredient_database = IngredientDatabase::try_from(input)?;
+    let input = Input::from_args(5)?;
+    let ingredient_database = input.parse(|lines| IngredientDatabase::try_from(lines.clone()))?;
 
     println!("Fresh ingredient count: {}", ingredient_database.fresh_ingredient_count());
     println!("Known fresh ingredient count: {}", ingredient_database.known_fresh_ingredient_count());
@@ -1,9 +1,9 @@
-use advent_of_code_2025::input_for_day;
+use advent_of_code_2025::Input;
 use advent_of_code_2025::day07::Manifold;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(7)?;
-    let mut manifold = Manifold::try_from(&input)?;
+    let input = Input::from_args(7)?;
+    let mut manifold = input.parse(|lines| Manifold::try_from(lines))?;
 
     println!("Manifold:\n{}", manifold);
 
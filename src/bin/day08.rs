@@ -1,10 +1,10 @@
-use advent_of_code_2025::input_for_day;
+use advent_of_code_2025::Input;
 use advent_of_code_2025::day08::Playground;
 use itertools::Itertools;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(8)?;
-    let mut playground = Playground::try_from(&input)?;
+    let input = Input::from_args(8)?;
+    let mut playground = input.parse(|lines| Playground::try_from(lines))?;
 
     let pairs = playground.closest_pairs();
 
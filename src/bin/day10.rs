@@ -1,14 +1,14 @@
-use advent_of_code_2025::input_for_day;
+use advent_of_code_2025::Input;
 use advent_of_code_2025::day10::Machine;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let _input = input_for_day(10)?;
+    let input = Input::from_args(10)?;
 
-    let machines = Machine::parse_all(&_input)?;
+    let machines = input.parse(Machine::parse_all)?;
 
     let min_indicator_light_button_presses: usize = machines.iter()
         .map(|machine| machine.min_indicator_light_button_presses())
-        .collect::<Result<Vec<usize>, String>>()?
+        .collect::<Result<Vec<usize>, _>>()?
         .iter()
         .sum();
 
@@ -1,9 +1,9 @@
-use advent_of_code_2025::input_for_day;
+use advent_of_code_2025::Input;
 use advent_of_code_2025::day11::DirectedGraph;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(11)?;
-    let graph = DirectedGraph::try_from(&input)?;
+    let input = Input::from_args(11)?;
+    let graph = input.parse(|lines| DirectedGraph::try_from(lines))?;
 
     let part_1_count = graph.paths_between("you", "out")?;
     println!("Part 1 paths: {}", part_1_count);
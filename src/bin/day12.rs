@@ -1,13 +1,154 @@
-use advent_of_code_2025::input_for_day;
-use advent_of_code_2025::day12::TreeFarm;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use advent_of_code_2025::{Input, Source};
+use advent_of_code_2025::day12::{
+    BacktrackingSolver, DefaultSolver, Objective, PackingSolver, PresentPlacement, RegionOutcome, RegionReport,
+    SolveOptions, TreeFarm,
+};
+
+// usage: day12 [INPUT] [--ascii] [--svg DIR] [--solver <backtracking | cp-sat>]
+//              [--threads N] [--timeout SECONDS] [--maximize <presents | cells>] [--verbose]
+struct Arguments {
+    input: Option<String>,
+    ascii: bool,
+    svg_directory: Option<PathBuf>,
+    solver: Box<dyn PackingSolver>,
+    options: SolveOptions,
+    // packs as much as possible into the regions that can't take everything
+    objective: Option<Objective>,
+    // reports each region's outcome on stderr as it's solved
+    verbose: bool,
+}
+
+impl Arguments {
+    fn parse() -> Result<Self, String> {
+        let mut arguments = Self {
+            input: None,
+            ascii: false,
+            svg_directory: None,
+            solver: Box::new(DefaultSolver::default()),
+            options: SolveOptions::default(),
+            objective: None,
+            verbose: false,
+        };
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--ascii" => arguments.ascii = true,
+                "--verbose" => arguments.verbose = true,
+                "--svg" => {
+                    let directory = args.next().ok_or("--svg needs a directory")?;
+                    arguments.svg_directory = Some(PathBuf::from(directory));
+                }
+                "--solver" => {
+                    let name = args.next().ok_or("--solver needs a name")?;
+                    arguments.solver = Self::solver(&name)?;
+                }
+                #[cfg(not(feature = "parallel"))]
+                "--threads" => {
+                    return Err("Parallel solving isn't compiled in; rebuild with --features parallel".to_string());
+                }
+                #[cfg(feature = "parallel")]
+                "--threads" => {
+                    let threads = args.next().ok_or("--threads needs a count")?;
+                    let threads = threads.parse().map_err(|e| format!("Invalid thread count '{}': {}", threads, e))?;
+                    arguments.options.threads = Some(threads);
+                }
+                "--timeout" => {
+                    let seconds = args.next().ok_or("--timeout needs a number of seconds")?;
+                    let seconds = seconds.parse().map_err(|e| format!("Invalid timeout '{}': {}", seconds, e))?;
+                    arguments.options.timeout = Some(Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())?);
+                }
+                "--maximize" => {
+                    let objective = args.next().ok_or("--maximize needs an objective")?;
+                    arguments.objective = Some(Objective::try_from(objective.as_str()).map_err(|e| e.to_string())?);
+                }
+                _ if arguments.input.is_none() => arguments.input = Some(arg),
+                _ => return Err(format!("Unexpected argument: {}", arg)),
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    fn solver(name: &str) -> Result<Box<dyn PackingSolver>, String> {
+        match name {
+            "backtracking" => Ok(Box::new(BacktrackingSolver)),
+            #[cfg(feature = "cp-sat")]
+            "cp-sat" => Ok(Box::new(advent_of_code_2025::day12::CpSatSolver)),
+            #[cfg(not(feature = "cp-sat"))]
+            "cp-sat" => Err("The cp-sat solver isn't compiled in; rebuild with --features cp-sat".to_string()),
+            _ => Err(format!("Unknown solver: {}", name)),
+        }
+    }
+
+    fn render(&self, tree_farm: &TreeFarm, region: usize, placements: &[PresentPlacement]) -> Result<(), Box<dyn std::error::Error>> {
+        let packing = tree_farm.packing_of(region, placements)?;
+
+        if self.ascii {
+            println!("Region {}:\n{}", region, packing);
+        }
+
+        if let Some(directory) = &self.svg_directory {
+            fs::create_dir_all(directory)?;
+            fs::write(directory.join(format!("region{:04}.svg", region)), packing.to_svg())?;
+        }
+
+        Ok(())
+    }
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = input_for_day(12)?;
+    let arguments = Arguments::parse()?;
+    let input = Input::read(Source::resolve(12, arguments.input.as_deref()))?;
+
+    let tree_farm = input.parse(|lines| TreeFarm::try_from(lines))?;
+
+    for duplicates in tree_farm.shape_library().duplicates() {
+        let indices: Vec<String> = duplicates.iter().map(|&shape| tree_farm.shape_indices()[shape].to_string()).collect();
+        eprintln!("Shapes {} are the same up to rotation and reflection", indices.join(", "));
+    }
+
+    let verbose = arguments.verbose;
+    let layouts = tree_farm.region_layouts_with(arguments.solver.as_ref(), &arguments.options, &mut |report: &RegionReport| {
+        if verbose {
+            eprintln!("Region {}: {} ({:.3?})", report.index, report.outcome, report.elapsed);
+        }
+    })?;
 
-    let tree_farm = TreeFarm::try_from(&input)?;
-    let valid_regions = tree_farm.valid_regions();
+    for layout in layouts.iter() {
+        match (&layout.placements, arguments.objective) {
+            (Some(placements), _) => arguments.render(&tree_farm, layout.region, placements)?,
+            (None, Some(objective)) => {
+                let optimum = tree_farm.maximize_with(layout.region, objective, arguments.solver.as_ref(), &arguments.options)?;
+
+                println!(
+                    "Region {}: {} presents, {} cells ({})",
+                    optimum.region,
+                    optimum.presents,
+                    optimum.cells,
+                    if optimum.optimal { "optimal" } else { "best found" },
+                );
+
+                arguments.render(&tree_farm, optimum.region, &optimum.placements)?;
+            }
+            (None, None) => {}
+        }
+    }
+
+    let valid_regions = layouts.iter().filter(|layout| layout.is_packable()).count();
+
+    let unknown_regions = layouts.iter().filter(|layout| layout.outcome == RegionOutcome::Unknown).count();
 
     println!("Valid regions: {}", valid_regions);
 
+    if unknown_regions > 0 {
+        eprintln!("{} regions timed out and weren't counted", unknown_regions);
+    }
+
     Ok(())
 }
@@ -1,99 +1,383 @@
-const MAX_POSITION: u8 = 100;
+use std::io::BufRead;
 
+use crate::{Error, Result};
+use crate::solution::Solution;
+
+// the counters stop at u64::MAX rather than wrapping around
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Safe {
-    dial_position: u8,
-    zero_position_count: u16,
-    zero_pass_count: u16,
+    dial: Dial,
+    zero_position_count: u64,
+    zero_pass_count: u64,
 }
 
 impl Safe {
     pub fn new() -> Self {
+        Self::with_dial(Dial::default())
+    }
+
+    pub fn builder() -> SafeBuilder {
+        SafeBuilder::default()
+    }
+
+    fn with_dial(dial: Dial) -> Self {
         Self {
-            dial_position: 50,
+            dial,
             zero_position_count: 0,
             zero_pass_count: 0,
-         }
+        }
     }
 
-    pub fn zero_position_count(&self) -> u16 {
+    pub fn dial(&self) -> Dial {
+        self.dial
+    }
+
+    pub fn zero_position_count(&self) -> u64 {
         self.zero_position_count
     }
 
-    pub fn zero_pass_count(&self) -> u16 {
+    pub fn zero_pass_count(&self) -> u64 {
         self.zero_pass_count
     }
 
-    pub fn apply_instructions(&mut self, instructions: Vec<String>) -> Result<(), String> {
+    pub fn apply_instructions(&mut self, instructions: Vec<String>) -> Result<()> {
         let rotations = Rotation::parse_all(&instructions)?;
         self.apply_rotations(rotations.iter().copied());
         Ok(())
     }
 
+    // applies each line as it comes; a bad line stops the stream with its (1-based) line number,
+    // leaving the rotations before it applied
+    pub fn apply_lines<I, S>(&mut self, lines: I) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for (index, line) in lines.into_iter().enumerate() {
+            self.apply_line(index, line.as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    // like `apply_lines`, without holding more than a line in memory
+    pub fn apply_reader<R: BufRead>(&mut self, reader: R) -> Result<()> {
+        for (index, line) in reader.lines().enumerate() {
+            self.apply_line(index, &line?)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_line(&mut self, index: usize, line: &str) -> Result<()> {
+        let rotation = Rotation::try_from(line).map_err(|e| e.offset(index, 0))?;
+        self.rotate(rotation);
+        Ok(())
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.clone())
+    }
+
+    pub fn restore(&mut self, checkpoint: &Checkpoint) {
+        *self = checkpoint.0.clone();
+    }
+
+    // applies the rotations as one batch, checking the policy after each; if it's broken the
+    // safe is rolled back to where it was before the batch
+    pub fn apply_batch<I: IntoIterator<Item = Rotation>>(
+        &mut self,
+        rotations: I,
+        policy: &impl BatchPolicy,
+    ) -> Result<Counters> {
+        let checkpoint = self.checkpoint();
+        let mut batch = Counters::default();
+
+        for (index, event) in self.trajectory(rotations).enumerate() {
+            batch.record(&event);
+
+            if !policy.allows(&batch) {
+                self.restore(&checkpoint);
+
+                return Err(Error::validation(format!(
+                    "Rotation {} of the batch breaks the policy, so the batch was rolled back", index + 1
+                )));
+            }
+        }
+
+        Ok(batch)
+    }
+
+    // the counters the rotations would add, leaving the safe as it is
+    pub fn simulate<I: IntoIterator<Item = Rotation>>(&self, rotations: I) -> Counters {
+        let mut safe = self.clone();
+
+        safe.trajectory(rotations).fold(Counters::default(), |mut batch, event| {
+            batch.record(&event);
+            batch
+        })
+    }
+
+    // applies the rotations lazily, one per event
+    pub fn trajectory<I: IntoIterator<Item = Rotation>>(&mut self, rotations: I) -> Trajectory<'_, I::IntoIter> {
+        Trajectory { safe: self, rotations: rotations.into_iter() }
+    }
+
+    // the puzzle's safe once the rotations are applied
+    fn after(rotations: &[Rotation]) -> Self {
+        let mut safe = Safe::new();
+        safe.apply_rotations(rotations.iter().copied());
+        safe
+    }
+
     fn apply_rotations<I: IntoIterator<Item = Rotation>>(&mut self, rotations: I) {
-        for rotation in rotations {
-            self.rotate(rotation);
+        self.trajectory(rotations).for_each(drop);
+    }
+
+    fn rotate(&mut self, rotation: Rotation) -> DialEvent {
+        let start = self.dial.position();
+        let zero_crossings = self.dial.turn(rotation.direction, rotation.steps);
+        let end = self.dial.position();
+
+        self.zero_pass_count = self.zero_pass_count.saturating_add(zero_crossings);
+
+        if end == 0 {
+            self.zero_position_count = self.zero_position_count.saturating_add(1);
+        }
+
+        DialEvent { rotation, start, end, zero_crossings, landed_on_zero: end == 0 }
+    }
+}
+
+pub struct Trajectory<'a, I> {
+    safe: &'a mut Safe,
+    rotations: I,
+}
+
+impl<I: Iterator<Item = Rotation>> Iterator for Trajectory<'_, I> {
+    type Item = DialEvent;
+
+    fn next(&mut self) -> Option<DialEvent> {
+        self.rotations.next().map(|rotation| self.safe.rotate(rotation))
+    }
+}
+
+// the whole state of a safe, to go back to with `Safe::restore`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint(Safe);
+
+// what a batch of rotations added to the safe's counters, stopping at u64::MAX like them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    pub zero_position_count: u64,
+    pub zero_pass_count: u64,
+}
+
+impl Counters {
+    fn record(&mut self, event: &DialEvent) {
+        self.zero_pass_count = self.zero_pass_count.saturating_add(event.zero_crossings);
+
+        if event.landed_on_zero {
+            self.zero_position_count = self.zero_position_count.saturating_add(1);
         }
     }
+}
+
+// checked after every rotation of a batch, against the counters the batch has added so far
+pub trait BatchPolicy {
+    fn allows(&self, batch: &Counters) -> bool;
+}
+
+impl<F: Fn(&Counters) -> bool> BatchPolicy for F {
+    fn allows(&self, batch: &Counters) -> bool {
+        self(batch)
+    }
+}
+
+// the dial may point at zero at most this many times per batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxZeroPasses(pub u64);
+
+impl BatchPolicy for MaxZeroPasses {
+    fn allows(&self, batch: &Counters) -> bool {
+        batch.zero_pass_count <= self.0
+    }
+}
+
+// `zero_crossings` counts every time the dial pointed at zero, landing on it included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DialEvent {
+    pub rotation: Rotation,
+    pub start: u64,
+    pub end: u64,
+    pub zero_crossings: u64,
+    pub landed_on_zero: bool,
+}
+
+// the puzzle's safe, unless told otherwise: a 100-position dial that starts at 50
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafeBuilder {
+    dial_size: u64,
+    start_position: u64,
+}
+
+impl SafeBuilder {
+    pub fn dial_size(mut self, dial_size: u64) -> Self {
+        self.dial_size = dial_size;
+        self
+    }
 
-    fn rotate(&mut self, rotation: Rotation) {
-        self.offset_dial(rotation.offset());
+    pub fn start_position(mut self, start_position: u64) -> Self {
+        self.start_position = start_position;
+        self
     }
 
-    fn offset_dial(&mut self, offset: i16) {
-        let old_position = self.dial_position as i16;
-        let complete_rotations = offset / MAX_POSITION as i16;
-        let remaining_steps = offset % MAX_POSITION as i16;
-        let new_position = self.dial_position as i16 + remaining_steps;
-        let wrapped_position = new_position.rem_euclid(MAX_POSITION as i16);
+    pub fn build(self) -> Result<Safe> {
+        Ok(Safe::with_dial(Dial::new(self.dial_size, self.start_position)?))
+    }
+}
 
-        self.zero_pass_count += complete_rotations.abs() as u16;
+impl Default for SafeBuilder {
+    fn default() -> Self {
+        let dial = Dial::default();
+        Self { dial_size: dial.size, start_position: dial.position }
+    }
+}
 
-        if (old_position != 0 &&new_position <= 0) || new_position >= MAX_POSITION as i16 {
-            self.zero_pass_count += 1;
+// positions 0 to size - 1, wrapping around in both directions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    size: u64,
+    position: u64,
+}
+
+impl Dial {
+    const DEFAULT_SIZE: u64 = 100;
+    const DEFAULT_POSITION: u64 = 50;
+
+    pub fn new(size: u64, position: u64) -> Result<Self> {
+        if size == 0 {
+            return Err(Error::validation("A dial needs at least one position"));
         }
 
-        if wrapped_position == 0 {
-            self.zero_position_count += 1;
+        if position >= size {
+            return Err(Error::validation(format!(
+                "Start position {} is off a dial of size {}", position, size
+            )));
         }
 
-        self.dial_position = wrapped_position as u8;
+        Ok(Self { size, position })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    // returns how many times the dial pointed at zero on the way, landing on it included
+    pub fn turn(&mut self, direction: Direction, steps: u64) -> u64 {
+        let (size, position, remainder) = (self.size as u128, self.position as u128, (steps % self.size) as u128);
+
+        // from zero, the next zero is a full turn away
+        let distance_to_zero = match direction {
+            _ if self.position == 0 => self.size,
+            Direction::Left => self.position,
+            Direction::Right => self.size - self.position,
+        };
+
+        self.position = match direction {
+            Direction::Left => ((position + size - remainder) % size) as u64,
+            Direction::Right => ((position + remainder) % size) as u64,
+        };
+
+        if steps < distance_to_zero {
+            return 0;
+        }
+
+        (steps - distance_to_zero) / self.size + 1
+    }
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Self { size: Self::DEFAULT_SIZE, position: Self::DEFAULT_POSITION }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Rotation {
+// each part turns the puzzle's safe through the rotations itself
+impl Solution for Vec<Rotation> {
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Rotation::parse_all(input)
+    }
+
+    fn part1(&self) -> Result<u64> {
+        Ok(Safe::after(self).zero_position_count())
+    }
+
+    fn part2(&self) -> Result<u64> {
+        Ok(Safe::after(self).zero_pass_count())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
     direction: Direction,
-    steps: u16,
+    steps: u64,
 }
 
 impl Rotation {
-    fn offset(&self) -> i16 {
-        self.direction.offset() as i16 * self.steps as i16
+    pub fn new(direction: Direction, steps: u64) -> Self {
+        Self { direction, steps }
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
     }
 
-    fn parse_all(instructions: &[String]) -> Result<Vec<Rotation>, String> {
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn parse_all(instructions: &[String]) -> Result<Vec<Rotation>> {
         instructions
             .iter()
-            .map(|s| Rotation::try_from(s.as_str()))
+            .enumerate()
+            .map(|(index, s)| Rotation::try_from(s.as_str()).map_err(|e| e.offset(index, 0)))
             .collect()
     }
 
-    fn parse_steps(steps_str: &str, full_value: &str) -> Result<u16, String> {
-        steps_str.parse::<u16>()
-            .map_err(|e| format!("'{}' - {}", full_value, e))
+    fn parse_steps(steps_str: &str, full_value: &str) -> Result<u64> {
+        steps_str.parse::<u64>()
+            .map_err(|e| Error::parse(2, format!("'{}' - {}", full_value, e)))
+    }
+}
+
+// the instruction format the input uses, e.g. "L68"
+impl std::fmt::Display for Rotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.direction {
+            Direction::Left => write!(f, "L{}", self.steps),
+            Direction::Right => write!(f, "R{}", self.steps),
+        }
     }
 }
 
 impl TryFrom<&str> for Rotation {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.is_empty() {
-            return Err(format!("Empty string: '{}'", value));
-        }
+    fn try_from(value: &str) -> Result<Self> {
+        // the direction may be any character, however many bytes it takes
+        let Some(direction) = value.chars().next() else {
+            return Err(Error::parse(1, "Empty instruction"));
+        };
 
-        let (direction_str, steps_str) = value.split_at(1);
+        let (direction_str, steps_str) = value.split_at(direction.len_utf8());
         let direction = Direction::try_from(direction_str)?;
         let steps = Rotation::parse_steps(steps_str, value)?;
 
@@ -104,19 +388,126 @@ impl TryFrom<&str> for Rotation {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-    Left,
-    Right,
+// finds the shortest rotation sequence that turns a dial to `target`. Unless told how often,
+// it lands on zero as few times as the target and passes allow, and passes zero as few times
+// as it can; the fewest rotations and the fewest passes never conflict, so the plan is
+// optimal for both
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoutePlanner {
+    target: u64,
+    zero_landings: Option<u64>,
+    zero_passes: Option<u64>,
 }
 
-impl Direction {
-    fn offset(&self) -> i8 {
-        match self {
-            Direction::Left => -1,
-            Direction::Right => 1,
+impl RoutePlanner {
+    // every landing is a rotation of its own, so this bounds the length of a plan
+    const MAX_LANDINGS: u64 = 1 << 20;
+
+    pub fn new(target: u64) -> Self {
+        Self { target, zero_landings: None, zero_passes: None }
+    }
+
+    // rotations that have to end on zero, the last one included
+    pub fn zero_landings(mut self, count: u64) -> Self {
+        self.zero_landings = Some(count);
+        self
+    }
+
+    // times the dial has to point at zero, landings included
+    pub fn zero_passes(mut self, count: u64) -> Self {
+        self.zero_passes = Some(count);
+        self
+    }
+
+    pub fn plan(&self, dial: Dial) -> Result<Vec<Rotation>> {
+        if self.target >= dial.size() {
+            return Err(Error::validation(format!(
+                "Target {} is off a dial of size {}", self.target, dial.size()
+            )));
+        }
+
+        // getting to zero from elsewhere takes a landing, and so does passing zero at all when
+        // the dial starts and ends there
+        let landings = self.zero_landings.unwrap_or_else(|| {
+            let passes = self.zero_passes.is_some_and(|passes| passes > 0);
+            u64::from(self.target == 0 && (dial.position() != 0 || passes))
+        });
+        let mut rotations = self.landing_rotations(dial, landings)?;
+
+        // every landing is a pass, and getting to the target from there needn't pass zero again
+        if self.target != 0 {
+            let position = if landings > 0 { 0 } else { dial.position() };
+
+            if position < self.target {
+                rotations.push(Rotation::new(Direction::Right, self.target - position));
+            } else if position > self.target {
+                rotations.push(Rotation::new(Direction::Left, position - self.target));
+            }
+        }
+
+        match self.zero_passes {
+            Some(passes) if passes < landings => Err(Error::validation(format!(
+                "Can't land on zero {} times while passing it only {} times", landings, passes
+            ))),
+            Some(passes) => Self::add_full_turns(dial, rotations, passes - landings),
+            None => Ok(rotations),
         }
     }
+
+    fn landing_rotations(&self, dial: Dial, landings: u64) -> Result<Vec<Rotation>> {
+        if self.target == 0 && landings == 0 && dial.position() != 0 {
+            return Err(Error::validation("Can't turn the dial to zero without landing on it"));
+        }
+
+        if landings > Self::MAX_LANDINGS {
+            return Err(Error::validation(format!(
+                "Can't plan {} landings on zero, at most {}", landings, Self::MAX_LANDINGS
+            )));
+        }
+
+        let first = match dial.position() {
+            0 => Rotation::new(Direction::Right, dial.size()),
+            position => Rotation::new(Direction::Left, position),
+        };
+
+        // after the first landing, each one is a full turn
+        let rest = std::iter::repeat_n(Rotation::new(Direction::Right, dial.size()), landings.saturating_sub(1) as usize);
+
+        Ok(std::iter::once(first).chain(rest).take(landings as usize).collect())
+    }
+
+    // each full turn added to a rotation passes zero once more without changing where it ends
+    fn add_full_turns(dial: Dial, mut rotations: Vec<Rotation>, turns: u64) -> Result<Vec<Rotation>> {
+        if turns == 0 {
+            return Ok(rotations);
+        }
+
+        // turning all the way round from zero would land on it
+        if rotations.is_empty() && dial.position() == 0 {
+            return Err(Error::validation("Can't pass zero from zero without landing on it"));
+        }
+
+        let steps = turns
+            .checked_mul(dial.size())
+            .ok_or_else(|| Error::validation(format!("{} full turns don't fit in a rotation", turns)))?;
+
+        match rotations.last_mut() {
+            Some(last) => {
+                last.steps = last.steps
+                    .checked_add(steps)
+                    .ok_or_else(|| Error::validation(format!("{} full turns don't fit in a rotation", turns)))?;
+            }
+            None => rotations.push(Rotation::new(Direction::Right, steps)),
+        }
+
+        Ok(rotations)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
 }
 
 impl std::fmt::Display for Direction {
@@ -129,14 +520,124 @@ impl std::fmt::Display for Direction {
 }
 
 impl TryFrom<&str> for Direction {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
+    fn try_from(s: &str) -> Result<Self> {
         match s {
             "L" => Ok(Direction::Left),
             "R" => Ok(Direction::Right),
-            _ => Err(format!("Invalid direction: {}", s)),
+            _ => Err(Error::parse(1, format!("Invalid direction: {}", s))),
         }
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn counts_zeros_on_any_dial() {
+        let mut safe = Safe::builder().dial_size(1_000_000).start_position(10).build().unwrap();
+        let instructions = ["L10", "R18446744073709551615", "L5"].map(String::from).to_vec();
+
+        safe.apply_instructions(instructions).unwrap();
+
+        assert_eq!(safe.dial().position(), 551_610);
+        assert_eq!(safe.zero_position_count(), 1);
+        assert_eq!(safe.zero_pass_count(), 1 + 18_446_744_073_709);
+
+        let mut safe = Safe::builder().dial_size(1).start_position(0).build().unwrap();
+        let spin = Rotation::new(Direction::Right, u64::MAX);
+
+        assert_eq!(safe.simulate([spin, spin]).zero_pass_count, u64::MAX);
+        safe.apply_rotations([spin, spin]);
+        assert_eq!((safe.zero_position_count(), safe.zero_pass_count()), (2, u64::MAX));
+
+        assert!(Safe::builder().dial_size(10).start_position(10).build().is_err());
+        assert!(Safe::builder().dial_size(0).build().is_err());
+    }
+
+    #[test]
+    fn traces_each_rotation() {
+        let instructions = ["L68", "L30", "R48", "R1000"].map(String::from);
+        let rotations = Rotation::parse_all(&instructions).unwrap();
+
+        let mut safe = Safe::new();
+        let events: Vec<(u64, u64, u64, bool)> = safe
+            .trajectory(rotations)
+            .map(|event| (event.start, event.end, event.zero_crossings, event.landed_on_zero))
+            .collect();
+
+        assert_eq!(events, vec![(50, 82, 1, false), (82, 52, 0, false), (52, 0, 1, true), (0, 0, 10, true)]);
+        assert_eq!((safe.zero_position_count(), safe.zero_pass_count()), (2, 12));
+    }
+
+    #[test]
+    fn streams_instructions_until_a_bad_line() {
+        let mut safe = Safe::new();
+        let error = safe.apply_reader("L50\nR205\nX3\nR1\n".as_bytes()).unwrap_err();
+
+        assert!(matches!(error, Error::Parse { line: 3, .. }));
+        assert_eq!(safe.dial().position(), 5);
+        assert_eq!((safe.zero_position_count(), safe.zero_pass_count()), (1, 3));
+
+        safe.apply_lines(["L5"]).unwrap();
+        assert_eq!(safe.zero_position_count(), 2);
+
+        let error = safe.apply_lines(["R10", "é5"]).unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 2, column: 1, .. }));
+        assert_eq!(safe.dial().position(), 10);
+    }
+
+    #[test]
+    fn rolls_back_batches_that_break_the_policy() {
+        let rotations = |instructions: &[&str]| {
+            Rotation::parse_all(&instructions.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap()
+        };
+
+        let mut safe = Safe::new();
+        let before = safe.clone();
+
+        let simulated = safe.simulate(rotations(&["L50", "R250"]));
+        assert_eq!(simulated, Counters { zero_position_count: 1, zero_pass_count: 3 });
+        assert_eq!(safe, before);
+
+        assert!(safe.apply_batch(rotations(&["L50", "R250"]), &MaxZeroPasses(2)).is_err());
+        assert_eq!(safe, before);
+
+        let applied = safe.apply_batch(rotations(&["L50", "R250"]), &|batch: &Counters| batch.zero_pass_count <= 3);
+        assert_eq!(applied.unwrap(), simulated);
+        assert_eq!(safe.zero_pass_count(), 3);
+    }
+
+    #[test]
+    fn plans_the_shortest_route() {
+        let plan = |start: u64, planner: RoutePlanner| {
+            let safe = Safe::builder().start_position(start).build().unwrap();
+            let rotations = planner.plan(safe.dial()).unwrap();
+
+            let mut end = safe.clone();
+            end.apply_lines(rotations.iter().map(Rotation::to_string)).unwrap();
+
+            let counters = safe.simulate(rotations.iter().copied());
+            (rotations.iter().join(" "), end.dial().position(), counters.zero_position_count, counters.zero_pass_count)
+        };
+
+        assert_eq!(plan(50, RoutePlanner::new(20)), ("L30".to_string(), 20, 0, 0));
+        assert_eq!(plan(50, RoutePlanner::new(0)), ("L50".to_string(), 0, 1, 1));
+        assert_eq!(plan(50, RoutePlanner::new(20).zero_landings(2)), ("L50 R100 R20".to_string(), 20, 2, 2));
+        assert_eq!(plan(0, RoutePlanner::new(0).zero_landings(1).zero_passes(3)), ("R300".to_string(), 0, 1, 3));
+        assert_eq!(plan(50, RoutePlanner::new(50).zero_passes(2)), ("R200".to_string(), 50, 0, 2));
+        assert_eq!(plan(0, RoutePlanner::new(0).zero_passes(2)), ("R200".to_string(), 0, 1, 2));
+        assert_eq!(plan(0, RoutePlanner::new(0)), ("".to_string(), 0, 0, 0));
+
+        let dial = Dial::default();
+        assert!(RoutePlanner::new(0).zero_landings(0).plan(dial).is_err());
+        assert!(RoutePlanner::new(20).zero_landings(2).zero_passes(1).plan(dial).is_err());
+        assert!(RoutePlanner::new(100).plan(dial).is_err());
+        assert!(RoutePlanner::new(0).zero_landings(u64::MAX).plan(dial).is_err());
+    }
+}
@@ -1,33 +1,104 @@
 use std::ops::RangeInclusive;
 use itertools::Itertools;
+use crate::{split_with_columns, Error, Result};
+use crate::solution::Solution;
 
-pub fn answer(input: &[String]) -> Result<u64, Box<dyn std::error::Error>> {
-    let bad_ids = input
-        .iter()
-        .flat_map(|s| s.split(","))
-        .map(|s: &str| parse_range(s.trim()))
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .flatten()
-        .filter(|&id| is_repeated(id))
-        ;
+pub struct ProductIdRanges {
+    ranges: Vec<RangeInclusive<u64>>,
+}
+
+impl ProductIdRanges {
+    pub fn bad_id_sum(&self, repetition: Repetition) -> u64 {
+        self.ranges
+            .iter()
+            .cloned()
+            .flatten()
+            .filter(|&id| repetition.matches(id))
+            .sum()
+    }
+}
+
+impl Solution for ProductIdRanges {
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        let ranges = input
+            .iter()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                split_with_columns(line, ',').map(move |(column, s)| {
+                    parse_range(s).map_err(|e| e.offset(index, column))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { ranges })
+    }
+
+    fn part1(&self) -> Result<u64> {
+        Ok(self.bad_id_sum(Repetition::ExactlyTwice))
+    }
+
+    fn part2(&self) -> Result<u64> {
+        Ok(self.bad_id_sum(Repetition::AtLeastTwice))
+    }
+}
+
+// how many times a block of digits has to repeat for an id made of nothing else to be bad
+#[derive(Debug, Clone, Copy)]
+pub enum Repetition {
+    ExactlyTwice,
+    AtLeastTwice,
+    Exactly(usize),
+    // given each number of repeats the id can be split into
+    Custom(fn(usize) -> bool),
+}
+
+impl Repetition {
+    // 1111 is "11" twice as well as "1" four times, so it matches any policy allowing either
+    pub fn matches(&self, number: u64) -> bool {
+        let string = number.to_string();
+
+        (2..=string.len())
+            .filter(|&count| string.len().is_multiple_of(count))
+            .filter(|&count| self.allows(count))
+            .any(|count| repeats_of_size(&string, string.len() / count))
+    }
 
-    Ok(bad_ids.sum())
+    fn allows(&self, count: usize) -> bool {
+        match self {
+            Repetition::ExactlyTwice => count == 2,
+            Repetition::AtLeastTwice => count >= 2,
+            Repetition::Exactly(k) => count == *k,
+            Repetition::Custom(predicate) => predicate(count),
+        }
+    }
+}
+
+pub fn answer(input: &[String], repetition: Repetition) -> Result<u64> {
+    Ok(ProductIdRanges::parse(input)?.bad_id_sum(repetition))
 }
 
-pub fn parse_range(string: &str) -> Result<RangeInclusive<u64>, Box<dyn std::error::Error>> {
+pub fn parse_range(string: &str) -> Result<RangeInclusive<u64>> {
+    let leading_whitespace = string.len() - string.trim_start().len();
+
     let (start_string, end_string) = string.trim().split_once("-")
-        .ok_or_else(|| format!("Invalid range format: '{}'", string))?;
+        .ok_or_else(|| Error::parse(leading_whitespace + 1, format!("Invalid range format: '{}'", string)))?;
 
-    let start = start_string.parse()?;
-    let end= end_string.parse()?;
+    let start = parse_id(start_string, leading_whitespace + 1)?;
+    let end = parse_id(end_string, leading_whitespace + start_string.len() + 2)?;
 
     Ok(start..=end)
 }
 
+fn parse_id(string: &str, column: usize) -> Result<u64> {
+    string.parse()
+        .map_err(|e| Error::parse(column, format!("Invalid id '{}': {}", string, e)))
+}
+
 pub fn is_repeated(number: u64) -> bool {
-    let string = number.to_string();
-    (1..=string.len() / 2).any(|size| repeats_of_size(&string, size))
+    Repetition::AtLeastTwice.matches(number)
 }
 
 pub fn repeats_of_size(string: &str, size: usize) -> bool {
@@ -43,3 +114,20 @@ pub fn repeats_of_size(string: &str, size: usize) -> bool {
         .unique()
         .count() <= 1
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_repetition_policies() {
+        let matching = |repetition: Repetition| {
+            [11, 1111, 123123, 121212, 1234, 7].into_iter().filter(|&id| repetition.matches(id)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching(Repetition::ExactlyTwice), vec![11, 1111, 123123]);
+        assert_eq!(matching(Repetition::AtLeastTwice), vec![11, 1111, 123123, 121212]);
+        assert_eq!(matching(Repetition::Exactly(3)), vec![121212]);
+        assert_eq!(matching(Repetition::Custom(|count| count > 2)), vec![1111, 121212]);
+    }
+}
@@ -1,6 +1,8 @@
 use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
+use crate::{Error, Result};
+use crate::solution::{NoAnswer, Solution};
 
 #[derive(Debug)]
 pub struct BatteryBank {
@@ -10,22 +12,23 @@ pub struct BatteryBank {
 impl BatteryBank {
     const ACTIVE_BATTERY_COUNT: usize = 12;
 
-    pub fn new(batteries: Vec<Battery>) -> Result<Self, String> {
+    pub fn new(batteries: Vec<Battery>) -> Result<Self> {
         if batteries.len() < Self::ACTIVE_BATTERY_COUNT {
-            return Err(
+            return Err(Error::validation(
                 format!("BatteryBank must have at least {} batteries, got {}",
                 Self::ACTIVE_BATTERY_COUNT, batteries.len())
-            );
+            ));
         }
 
         Ok(Self { batteries })
     }
 
-    pub fn parse_all(strings: &[String]) -> Result<Vec<Self>, String> {
+    pub fn parse_all(strings: &[String]) -> Result<Vec<Self>> {
         strings
             .iter()
-            .map(|s| s.parse())
-            .collect::<Result<Vec<_>, _>>()
+            .enumerate()
+            .map(|(index, s)| s.parse().map_err(|e: Error| e.offset(index, 0)))
+            .collect::<Result<Vec<_>>>()
     }
 
     pub fn maximum_joltage(&self) -> u64 {
@@ -77,6 +80,23 @@ impl BatteryBank {
     }
 }
 
+impl Solution for Vec<BatteryBank> {
+    type Part1 = NoAnswer;
+    type Part2 = u128;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        BatteryBank::parse_all(input)
+    }
+
+    fn part1(&self) -> Result<NoAnswer> {
+        Ok(NoAnswer)
+    }
+
+    fn part2(&self) -> Result<u128> {
+        Ok(self.iter().map(|bank| bank.maximum_joltage() as u128).sum())
+    }
+}
+
 impl fmt::Display for BatteryBank {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for battery in &self.batteries {
@@ -88,13 +108,14 @@ impl fmt::Display for BatteryBank {
 }
 
 impl FromStr for BatteryBank {
-    type Err = String;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         let batteries: Vec<Battery> = s
             .chars()
-            .map(|c| Battery::try_from(c))
-            .collect::<Result<Vec<_>, _>>()?;
+            .enumerate()
+            .map(|(index, c)| Battery::try_from(c).map_err(|e| e.offset(0, index)))
+            .collect::<Result<Vec<_>>>()?;
 
         BatteryBank::new(batteries)
     }
@@ -106,17 +127,17 @@ pub struct Battery {
 }
 
 impl Battery {
-    pub fn new(joltage: u8) -> Result<Self, String> {
+    pub fn new(joltage: u8) -> Result<Self> {
         Self::try_from(joltage)
     }
 }
 
 impl TryFrom<char> for Battery {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(c: char) -> Result<Self, Self::Error> {
+    fn try_from(c: char) -> Result<Self> {
         let joltage = c.to_digit(10)
-            .ok_or_else(|| format!("Character '{}' is not a digit", c))?
+            .ok_or_else(|| Error::parse(1, format!("Character '{}' is not a digit", c)))?
             as u8;
 
         Battery::try_from(joltage)
@@ -124,11 +145,11 @@ impl TryFrom<char> for Battery {
 }
 
 impl TryFrom<u8> for Battery {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(joltage: u8) -> Result<Self, Self::Error> {
+    fn try_from(joltage: u8) -> Result<Self> {
         if !(1..=9).contains(&joltage) {
-            return Err(format!("Joltage must be between 1 and 9, got {}", joltage));
+            return Err(Error::validation(format!("Joltage must be between 1 and 9, got {}", joltage)));
         }
 
         Ok(Self { joltage })
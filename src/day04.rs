@@ -1,5 +1,7 @@
 use std::fmt;
 use std::convert::TryFrom;
+use crate::{Error, Result};
+use crate::solution::Solution;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FactoryFloor {
@@ -106,21 +108,43 @@ impl FactoryFloor {
     }
 }
 
+impl Solution for FactoryFloor {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Self::try_from(&input.to_vec())
+    }
+
+    fn part1(&self) -> Result<usize> {
+        Ok(self.accessible_roll_count())
+    }
+
+    fn part2(&self) -> Result<usize> {
+        let mut factory_floor = self.clone();
+        factory_floor.remove_accessible_rolls();
+
+        Ok(self.roll_count() - factory_floor.roll_count())
+    }
+}
+
 impl TryFrom<&Vec<String>> for FactoryFloor {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &Vec<String>) -> Result<Self, Self::Error> {
+    fn try_from(value: &Vec<String>) -> Result<Self> {
         let grid: Vec<Vec<Cell>> = value
             .iter()
-            .map(|row| {
+            .enumerate()
+            .map(|(i, row)| {
                 row.chars()
-                    .map(|c| Cell::try_from(c))
-                    .collect::<Result<Vec<_>, _>>()
+                    .enumerate()
+                    .map(|(j, c)| Cell::try_from(c).map_err(|e| e.offset(i, j)))
+                    .collect::<Result<Vec<_>>>()
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>>>()?;
 
         if grid.is_empty() {
-            return Err("Grid cannot be empty".to_string());
+            return Err(Error::validation("Grid cannot be empty"));
         }
 
         Ok(Self { grid })
@@ -152,13 +176,13 @@ pub enum Cell {
 }
 
 impl TryFrom<char> for Cell {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(c: char) -> Result<Self, Self::Error> {
+    fn try_from(c: char) -> Result<Self> {
         match c {
             '.' => Ok(Cell::Empty),
             '@' => Ok(Cell::PaperRoll),
-            _ => Err(format!("Invalid cell character: '{}'", c)),
+            _ => Err(Error::parse(1, format!("Invalid cell character: '{}'", c))),
         }
     }
 }
@@ -1,5 +1,7 @@
 use std::ops::RangeInclusive;
 use std::fmt;
+use crate::{Error, Result};
+use crate::solution::Solution;
 
 #[derive(Debug)]
 pub struct IngredientDatabase {
@@ -39,17 +41,18 @@ impl IngredientDatabase {
             .any(|range| range.contains(&ingredient))
     }
 
-    fn parse_range(line: &str) -> Result<RangeInclusive<u64>, String> {
-        let (min, max) = line.split_once('-').ok_or("Invalid line")?;
+    fn parse_range(line: &str) -> Result<RangeInclusive<u64>> {
+        let (min_str, max_str) = line.split_once('-')
+            .ok_or_else(|| Error::parse(1, format!("Invalid range: '{}'", line)))?;
 
-        let min = Self::parse_u64(min)?;
-        let max = Self::parse_u64(max)?;
+        let min = Self::parse_u64(min_str)?;
+        let max = Self::parse_u64(max_str).map_err(|e| e.offset(0, min_str.len() + 1))?;
 
         Ok(min..=max)
     }
 
-    fn parse_u64(s: &str) -> Result<u64, String> {
-        s.parse::<u64>().map_err(|e| e.to_string())
+    fn parse_u64(s: &str) -> Result<u64> {
+        s.parse::<u64>().map_err(|e| Error::parse(1, format!("Invalid number '{}': {}", s, e)))
     }
 
     fn range_measure(range: &RangeInclusive<u64>) -> u64 {
@@ -59,7 +62,7 @@ impl IngredientDatabase {
     fn remove_overlap(
         range_to_adjust: RangeInclusive<u64>,
         existing_range: &RangeInclusive<u64>,
-    ) -> Result<RangeInclusive<u64>, ()> {
+    ) -> std::result::Result<RangeInclusive<u64>, ()> {
         let mut start = *range_to_adjust.start();
         let mut end = *range_to_adjust.end();
 
@@ -101,21 +104,38 @@ impl IngredientDatabase {
     }
 }
 
+impl Solution for IngredientDatabase {
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Self::try_from(input.to_vec())
+    }
+
+    fn part1(&self) -> Result<usize> {
+        Ok(self.fresh_ingredient_count())
+    }
+
+    fn part2(&self) -> Result<u64> {
+        Ok(self.known_fresh_ingredient_count())
+    }
+}
+
 impl TryFrom<Vec<String>> for IngredientDatabase {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
-        let mut iter = value.into_iter();
+    fn try_from(value: Vec<String>) -> Result<Self> {
+        let mut iter = value.into_iter().enumerate();
 
         let fresh_ingredient_ranges: Vec<RangeInclusive<u64>> = iter
             .by_ref()
-            .take_while(|line| !line.is_empty())
-            .map(|line| Self::parse_range(&line))
-            .collect::<Result<_, _>>()?;
+            .take_while(|(_, line)| !line.is_empty())
+            .map(|(index, line)| Self::parse_range(&line).map_err(|e| e.offset(index, 0)))
+            .collect::<Result<_>>()?;
 
         let available_ingredients: Vec<u64> = iter
-            .map(|line| Self::parse_u64(&line))
-            .collect::<Result<_, _>>()?;
+            .map(|(index, line)| Self::parse_u64(&line).map_err(|e| e.offset(index, 0)))
+            .collect::<Result<_>>()?;
 
         Ok(Self::new(fresh_ingredient_ranges, available_ingredients))
     }
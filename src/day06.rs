@@ -1,3 +1,33 @@
+use crate::{Error, Result};
+use crate::solution::Solution;
+
+pub struct Worksheets {
+    part1: Worksheet<Part1>,
+    part2: Worksheet<Part2>,
+}
+
+impl Solution for Worksheets {
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        let input = input.to_vec();
+
+        Ok(Self {
+            part1: Worksheet::try_from(&input)?,
+            part2: Worksheet::try_from(&input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<u64> {
+        Ok(self.part1.answer())
+    }
+
+    fn part2(&self) -> Result<u64> {
+        Ok(self.part2.answer())
+    }
+}
+
 pub struct Worksheet<P> {
     problems: Vec<Problem>,
     _part: std::marker::PhantomData<P>,
@@ -32,18 +62,51 @@ impl<P> Worksheet<P> {
 
         result
     }
+
+    // positions are lost once the grid is transformed, so check characters up front
+    fn validate_characters(lines: &[String]) -> Result<()> {
+        let Some((operation_line, number_lines)) = lines.split_last() else {
+            return Err(Error::validation("Worksheet cannot be empty"));
+        };
+
+        let mut invalid_characters = number_lines
+            .iter()
+            .enumerate()
+            .flat_map(|(line_index, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, c)| !c.is_ascii_digit() && *c != ' ')
+                    .map(move |(column_index, c)| (line_index, column_index, c))
+            })
+            .chain(
+                operation_line
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, c)| !matches!(c, '+' | '*' | ' '))
+                    .map(|(column_index, c)| (number_lines.len(), column_index, c)),
+            );
+
+        if let Some((line_index, column_index, c)) = invalid_characters.next() {
+            return Err(Error::parse(1, format!("Invalid character: '{}'", c))
+                .offset(line_index, column_index));
+        }
+
+        Ok(())
+    }
 }
 
 impl<P: GridTransformation> TryFrom<&Vec<String>> for Worksheet<P> {
-    type Error = String;
+    type Error = Error;
+
+    fn try_from(value: &Vec<String>) -> Result<Self> {
+        Self::validate_characters(value)?;
 
-    fn try_from(value: &Vec<String>) -> Result<Self, Self::Error> {
         let problem_grid = Self::split_at_columns(value);
         let transformed_grid = P::transform_grid(&problem_grid);
 
         let problems: Vec<Problem> = transformed_grid.iter()
             .map(|row| Problem::try_from(row))
-            .collect::<Result<Vec<Problem>, String>>()?;
+            .collect::<Result<Vec<Problem>>>()?;
 
         Ok(Worksheet {
             problems,
@@ -116,13 +179,13 @@ enum Operation {
 }
 
 impl TryFrom<&str> for Operation {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
+    fn try_from(value: &str) -> Result<Self> {
         match value {
             "+" => Ok(Operation::Add),
             "*" => Ok(Operation::Multiply),
-            _ => Err(format!("Invalid operation: {}", value)),
+            _ => Err(Error::validation(format!("Invalid operation: '{}'", value))),
         }
     }
 }
@@ -135,9 +198,9 @@ struct Problem {
 }
 
 impl Problem {
-    fn parse_u16s(value: &[String]) -> Result<Vec<u16>, String> {
+    fn parse_u16s(value: &[String]) -> Result<Vec<u16>> {
         value.iter()
-            .map(|s| s.parse::<u16>().map_err(|e| format!("Invalid number: {}", e)))
+            .map(|s| s.parse::<u16>().map_err(|e| Error::validation(format!("Invalid number '{}': {}", s, e))))
             .collect()
     }
 
@@ -150,10 +213,10 @@ impl Problem {
 }
 
 impl TryFrom<&Vec<String>> for Problem {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &Vec<String>) -> Result<Self, Self::Error> {
-        let last_element = value.last().ok_or("No last element")?;
+    fn try_from(value: &Vec<String>) -> Result<Self> {
+        let last_element = value.last().ok_or_else(|| Error::validation("Problem has no operation"))?;
         let operation = Operation::try_from(last_element.as_str())?;
         let numbers = Self::parse_u16s(&value[..value.len() - 1])?;
 
@@ -1,3 +1,7 @@
+use crate::{Error, Result};
+use crate::solution::Solution;
+
+#[derive(Clone)]
 pub struct Manifold {
     grid: Vec<Vec<Cell>>
 }
@@ -47,6 +51,12 @@ impl Manifold {
             .sum()
     }
 
+    fn with_beam(&self) -> Self {
+        let mut manifold = self.clone();
+        manifold.extend_beam();
+        manifold
+    }
+
     fn illuminated_split_count(row: &[Cell]) -> usize {
         row.iter()
             .filter(|cell| cell.cell_type == CellType::Splitter && cell.illuminated)
@@ -67,20 +77,40 @@ impl Manifold {
         cell.timeline_count = cell.timeline_count + timeline_count;
     }
 
-    fn parse_line(line: &str) -> Result<Vec<Cell>, String> {
+    fn parse_line(line: &str) -> Result<Vec<Cell>> {
         line.chars()
-            .map(|c| Cell::try_from(c))
-            .collect::<Result<Vec<Cell>, String>>()
+            .enumerate()
+            .map(|(index, c)| Cell::try_from(c).map_err(|e| e.offset(0, index)))
+            .collect::<Result<Vec<Cell>>>()
+    }
+}
+
+impl Solution for Manifold {
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Self::try_from(&input.to_vec())
+    }
+
+    // each part traces the beam through its own copy
+    fn part1(&self) -> Result<usize> {
+        Ok(self.with_beam().split_count())
+    }
+
+    fn part2(&self) -> Result<u64> {
+        Ok(self.with_beam().timeline_count())
     }
 }
 
 impl TryFrom<&Vec<String>> for Manifold {
-    type Error = Box<dyn std::error::Error>;
+    type Error = Error;
 
-    fn try_from(value: &Vec<String>) -> Result<Self, Self::Error> {
+    fn try_from(value: &Vec<String>) -> Result<Self> {
         let grid: Vec<Vec<Cell>> = value.iter()
-            .map(|line| Self::parse_line(line))
-            .collect::<Result<Vec<Vec<Cell>>, String>>()?;
+            .enumerate()
+            .map(|(index, line)| Self::parse_line(line).map_err(|e| e.offset(index, 0)))
+            .collect::<Result<Vec<Vec<Cell>>>>()?;
 
         Ok(Self {grid})
     }
@@ -100,14 +130,14 @@ impl std::fmt::Display for Manifold {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 enum CellType {
     Source,
     Space,
     Splitter,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Cell {
     cell_type: CellType,
     illuminated: bool,
@@ -135,14 +165,14 @@ impl Cell {
 }
 
 impl TryFrom<char> for Cell {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(c: char) -> Result<Self, Self::Error> {
+    fn try_from(c: char) -> Result<Self> {
         match c {
             'S' => Ok(Cell::new(CellType::Source)),
             '.' => Ok(Cell::new(CellType::Space)),
             '^' => Ok(Cell::new(CellType::Splitter)),
-            _ => Err(format!("Invalid cell character: {}", c)),
+            _ => Err(Error::parse(1, format!("Invalid cell character: {}", c))),
         }
     }
 }
\ No newline at end of file
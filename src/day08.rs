@@ -2,14 +2,18 @@ use nalgebra::Vector3;
 use itertools::Itertools;
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use crate::{split_with_columns, Error, Result};
+use crate::solution::Solution;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Playground {
     junction_boxes: Vec<JunctionBox>,
     circuit_collection: CircuitCollection,
 }
 
 impl Playground {
+    const CONNECTION_COUNT: usize = 1000;
+
     fn new(junction_boxes: Vec<JunctionBox>) -> Self {
         let circuits = CircuitCollection::new(junction_boxes.len());
         Self {junction_boxes, circuit_collection: circuits}
@@ -49,19 +53,59 @@ impl Playground {
     }
 }
 
+impl Solution for Playground {
+    type Part1 = u32;
+    type Part2 = i32;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Self::try_from(&input.to_vec())
+    }
+
+    fn part1(&self) -> Result<u32> {
+        let mut playground = self.clone();
+
+        for pair in self.closest_pairs().into_iter().take(Self::CONNECTION_COUNT) {
+            playground.connect(pair);
+        }
+
+        let product = playground.circuits()
+            .sorted_by_key(|circuit| -(circuit.len() as i32))
+            .take(3)
+            .map(|circuit| circuit.len() as u32)
+            .product();
+
+        Ok(product)
+    }
+
+    fn part2(&self) -> Result<i32> {
+        let mut playground = self.clone();
+
+        let last_pair = self.closest_pairs()
+            .into_iter()
+            .find(|&pair| {
+                playground.connect(pair);
+                playground.circuits().count() == 1
+            })
+            .ok_or_else(|| Error::solver("Junction boxes never form a single circuit"))?;
+
+        Ok(self.x(last_pair.0) * self.x(last_pair.1))
+    }
+}
+
 impl TryFrom<&Vec<String>> for Playground {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &Vec<String>) -> Result<Self, Self::Error> {
-        let junction_boxes: Result<Vec<JunctionBox>, String> = value.iter()
-            .map(|line| JunctionBox::try_from(line))
+    fn try_from(value: &Vec<String>) -> Result<Self> {
+        let junction_boxes: Result<Vec<JunctionBox>> = value.iter()
+            .enumerate()
+            .map(|(index, line)| JunctionBox::try_from(line).map_err(|e| e.offset(index, 0)))
             .collect();
 
         Ok(Self::new(junction_boxes?))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct JunctionBox{
     position: Vector3<i32>,
 }
@@ -83,24 +127,25 @@ impl JunctionBox {
 }
 
 impl TryFrom<&String> for JunctionBox {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &String) -> Result<Self, Self::Error> {
-        let coordinates: Vec<i32> = value
-            .split(',')
-            .map(|s| s.parse::<i32>().map_err(|e| format!("Parse error: {}", e)))
-            .collect::<Result<Vec<i32>, String>>()?;
+    fn try_from(value: &String) -> Result<Self> {
+        let coordinates: Vec<i32> = split_with_columns(value, ',')
+            .map(|(column, s)| {
+                s.parse::<i32>().map_err(|e| Error::parse(1, format!("Parse error: {}", e)).offset(0, column))
+            })
+            .collect::<Result<Vec<i32>>>()?;
 
         let position = match coordinates.as_slice() {
             [x, y, z] => Vector3::new(*x, *y, *z),
-            _ => return Err(format!("Expected 3 coordinates, got {}", coordinates.len())),
+            _ => return Err(Error::parse(1, format!("Expected 3 coordinates, got {}", coordinates.len()))),
         };
 
         Ok(Self { position })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CircuitCollection {
     circuits: Vec<HashSet<usize>>,
     circuits_by_node: Vec<usize>,
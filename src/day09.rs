@@ -1,5 +1,7 @@
 use itertools::Itertools;
 use nalgebra::Vector2;
+use crate::{split_with_columns, Error, Result};
+use crate::solution::Solution;
 
 pub struct Floor {
     red_tiles: Vec<Tile>,
@@ -49,14 +51,36 @@ impl Floor {
     }
 }
 
+impl Solution for Floor {
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Self::try_from(&input.to_vec())
+    }
+
+    fn part1(&self) -> Result<u64> {
+        Ok(self.largest_rectangle_area(Filter::All))
+    }
+
+    fn part2(&self) -> Result<u64> {
+        Ok(self.largest_rectangle_area(Filter::ValidOnly))
+    }
+}
+
 impl TryFrom<&Vec<String>> for Floor {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &Vec<String>) -> Result<Self, Self::Error> {
+    fn try_from(value: &Vec<String>) -> Result<Self> {
         let tiles: Vec<Tile> = value
             .iter()
-            .map(|line| Tile::try_from(line.as_str()))
-            .collect::<Result<Vec<Tile>, _>>()?;
+            .enumerate()
+            .map(|(index, line)| Tile::try_from(line.as_str()).map_err(|e| e.offset(index, 0)))
+            .collect::<Result<Vec<Tile>>>()?;
+
+        if tiles.len() < 2 {
+            return Err(Error::validation(format!("Expected at least 2 red tiles, found {}", tiles.len())));
+        }
 
         Ok(Floor { red_tiles: tiles })
     }
@@ -81,22 +105,21 @@ impl Tile {
 }
 
 impl TryFrom<&str> for Tile {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let coordinates: Vec<i64> = value
-            .split(',')
-            .map(|c| {
+    fn try_from(value: &str) -> Result<Self> {
+        let coordinates: Vec<i64> = split_with_columns(value, ',')
+            .map(|(column, c)| {
                 c.parse::<i64>()
-                    .map_err(|e| format!("Failed to parse '{}': {}", c, e))
+                    .map_err(|e| Error::parse(1, format!("Failed to parse '{}': {}", c, e)).offset(0, column))
             })
-            .collect::<Result<_, _>>()?;
+            .collect::<Result<_>>()?;
 
         if coordinates.len() != 2 {
-            return Err(format!(
+            return Err(Error::parse(1, format!(
                 "Expected 2 coordinates, found {}",
                 coordinates.len()
-            ));
+            )));
         }
 
         Ok(Tile::new(coordinates[0], coordinates[1]))
@@ -2,6 +2,7 @@ use std::collections::HashSet;
 use good_lp::*;
 use itertools::izip;
 use nalgebra::DMatrix;
+use crate::{split_with_columns, Error, Result};
 
 #[derive(Debug)]
 pub struct Machine {
@@ -19,11 +20,14 @@ impl Machine {
         }
     }
 
-    pub fn parse_all(values: &Vec<String>) -> Result<Vec<Machine>, String> {
-        values.iter().map(|value| Machine::try_from(value)).collect()
+    pub fn parse_all(values: &Vec<String>) -> Result<Vec<Machine>> {
+        values.iter()
+            .enumerate()
+            .map(|(index, value)| Machine::try_from(value).map_err(|e| e.offset(index, 0)))
+            .collect()
     }
 
-    pub fn min_indicator_light_button_presses(&self) -> Result<usize, String> {
+    pub fn min_indicator_light_button_presses(&self) -> Result<usize> {
         let initial_state = self.initial_indicator_light_state();
         let transitions = self.indicator_light_transitions();
 
@@ -47,10 +51,10 @@ impl Machine {
             .collect()
     }
 
-    pub fn min_joltage_button_presses(&self) -> Result<u16, String> {
+    pub fn min_joltage_button_presses(&self) -> Result<u16> {
         let basis_vectors = self.joltage_basis_vectors();
         let solver = ILPSolver::new(basis_vectors, self.joltages_target.clone());
-        let solution = solver.solution().map_err(|e| e.to_string())?;
+        let solution = solver.solution().map_err(|e| Error::solver(e.to_string()))?;
 
         Ok(solution.iter().sum())
     }
@@ -76,46 +80,90 @@ impl Machine {
 
         new_state
     }
-}
-
-impl TryFrom<&String> for Machine {
-    type Error = String;
 
-    fn try_from(value: &String) -> Result<Self, Self::Error> {
-        let sections: Vec<&str> =  value.split_whitespace().collect();
+    fn parse_list<T: std::str::FromStr>(value: &str, open: char, close: char) -> Result<Vec<T>>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let inner = value
+            .strip_prefix(open)
+            .and_then(|inner| inner.strip_suffix(close))
+            .ok_or_else(|| Error::parse(1, format!("expected '{}...{}', got '{}'", open, close, value)))?;
+
+        // the pieces start after the opening bracket
+        split_with_columns(inner, ',')
+            .map(|(column, s)| {
+                s.parse::<T>()
+                    .map_err(|e| Error::parse(2, format!("invalid number: {}", e)).offset(0, column))
+            })
+            .collect()
+    }
 
-        let indicator_lights_target_string = sections[0];
-        let buttons_strings = sections[1..sections.len() - 1].to_vec();
-        let joltages_target_string = sections[sections.len() - 1];
+    fn parse_indicator_lights(value: &str) -> Result<Vec<bool>> {
+        let inner = value
+            .strip_prefix('[')
+            .and_then(|inner| inner.strip_suffix(']'))
+            .ok_or_else(|| Error::parse(1, format!("expected '[...]', got '{}'", value)))?;
 
-        let indicator_lights_target: Vec<bool> = indicator_lights_target_string
-            .trim_start_matches('[')
-            .trim_end_matches(']')
+        inner
             .chars()
-            .map(|c| match c {
+            .enumerate()
+            .map(|(index, c)| match c {
                 '.' => Ok(false),
                 '#' => Ok(true),
-                _ => Err(format!("invalid character: {}", c))
+                _ => Err(Error::parse(index + 2, format!("invalid character: {}", c)))
             })
-            .collect::<Result<Vec<bool>, String>>()?;
+            .collect()
+    }
+}
+
+// good_lp's prelude exports its own `Solution` trait
+impl crate::solution::Solution for Vec<Machine> {
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Machine::parse_all(&input.to_vec())
+    }
+
+    fn part1(&self) -> Result<usize> {
+        self.iter()
+            .map(|machine| machine.min_indicator_light_button_presses())
+            .sum()
+    }
+
+    fn part2(&self) -> Result<u64> {
+        self.iter()
+            .map(|machine| machine.min_joltage_button_presses().map(u64::from))
+            .sum()
+    }
+}
+
+impl TryFrom<&String> for Machine {
+    type Error = Error;
+
+    fn try_from(value: &String) -> Result<Self> {
+        let sections: Vec<(usize, &str)> = split_with_columns(value, ' ')
+            .filter(|(_, section)| !section.is_empty())
+            .collect();
+
+        let [(lights_column, indicator_lights_target_string), buttons_strings @ .., (joltages_column, joltages_target_string)] =
+            sections.as_slice()
+        else {
+            return Err(Error::parse(1, "expected indicator lights, buttons and joltages"));
+        };
+
+        let indicator_lights_target = Self::parse_indicator_lights(indicator_lights_target_string)
+            .map_err(|e| e.offset(0, *lights_column))?;
 
         let buttons: Vec<Vec<usize>> = buttons_strings.iter()
-            .map(|buttons_string| {
-                buttons_string
-                    .trim_start_matches('(')
-                    .trim_end_matches(')')
-                    .split(',')
-                    .map(|s| s.parse::<usize>().map_err(|e| format!("invalid number: {}", e)))
-                    .collect::<Result<Vec<usize>, String>>()
+            .map(|(column, buttons_string)| {
+                Self::parse_list(buttons_string, '(', ')').map_err(|e| e.offset(0, *column))
             })
-            .collect::<Result<Vec<Vec<usize>>, String>>()?;
+            .collect::<Result<Vec<Vec<usize>>>>()?;
 
-        let joltages_target: Vec<u16> = joltages_target_string
-            .trim_start_matches("{")
-            .trim_end_matches("}")
-            .split(",")
-            .map(|s| s.parse::<u16>().map_err(|e| format!("invalid number: {}", e)))
-            .collect::<Result<Vec<u16>, String>>()?;
+        let joltages_target: Vec<u16> = Self::parse_list(joltages_target_string, '{', '}')
+            .map_err(|e| e.offset(0, *joltages_column))?;
 
         Ok(Self::new(indicator_lights_target, buttons, joltages_target))
     }
@@ -139,10 +187,10 @@ impl<S: Clone + std::hash::Hash + Eq> StateMachine<S> {
         Self { transitions, target_state, known_states, visited_states, transition_count: 0 }
     }
 
-    pub fn min_transition_count(&mut self) -> Result<usize, String> {
+    pub fn min_transition_count(&mut self) -> Result<usize> {
         loop {
             if self.transition_count > Self::MAX_TRANSITIONS {
-                return Err(format!("Maximum transition count ({}) exceeded", Self::MAX_TRANSITIONS));
+                return Err(Error::solver(format!("Maximum transition count ({}) exceeded", Self::MAX_TRANSITIONS)));
             }
 
             if let Some(count) = self.step() {
@@ -206,7 +254,7 @@ impl ILPSolver {
         Self { basis_vectors, target }
     }
 
-    pub fn solution(&self) -> Result<Vec<u16>, good_lp::ResolutionError> {
+    pub fn solution(&self) -> std::result::Result<Vec<u16>, good_lp::ResolutionError> {
         let (problem, variables) = self.set_up_problem();
         let solution = problem.solve()?;
 
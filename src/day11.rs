@@ -1,5 +1,7 @@
 use std::collections::VecDeque;
 use itertools::Itertools;
+use crate::{Error, Result};
+use crate::solution::Solution;
 
 enum Direction {
     Forward,
@@ -20,7 +22,7 @@ impl DirectedGraph {
         Self { nodes, adjacency, topological_order }
     }
 
-    pub fn paths_between(&self, from_str: &str, to_str: &str) -> Result<u64, String> {
+    pub fn paths_between(&self, from_str: &str, to_str: &str) -> Result<u64> {
         let from = self.node_index(from_str)?;
         let to = self.node_index(to_str)?;
 
@@ -28,13 +30,13 @@ impl DirectedGraph {
         Ok(path_counts[to])
     }
 
-    pub fn paths_between_including(&self, from_str: &str, to_str: &str, including_strs: &Vec<&str>) -> Result<u64, String> {
+    pub fn paths_between_including(&self, from_str: &str, to_str: &str, including_strs: &Vec<&str>) -> Result<u64> {
         if including_strs.is_empty() {
             return self.paths_between(from_str, to_str);
         }
 
         if !including_strs.iter().all_unique() {
-            return Err("Including nodes must be unique".to_string());
+            return Err(Error::validation("Including nodes must be unique"));
         }
 
         let from = self.node_index(from_str)?;
@@ -150,13 +152,13 @@ impl DirectedGraph {
         self.topological_order[from_pos..=to_pos].iter().copied()
     }
 
-    fn node_index(&self, node: &str) -> Result<usize, String> {
+    fn node_index(&self, node: &str) -> Result<usize> {
         self.nodes.iter()
             .position(|n| n == node)
-            .ok_or_else(|| format!("Node '{}' not found", node))
+            .ok_or_else(|| Error::validation(format!("Node '{}' not found", node)))
     }
 
-    fn node_indices(&self, nodes: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Vec<usize>, String> {
+    fn node_indices(&self, nodes: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Vec<usize>> {
         nodes.into_iter()
             .map(|node| self.node_index(node.as_ref()))
             .collect()
@@ -179,16 +181,33 @@ impl DirectedGraph {
     }
 }
 
+impl Solution for DirectedGraph {
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &[String]) -> Result<Self> {
+        Self::try_from(&input.to_vec())
+    }
+
+    fn part1(&self) -> Result<u64> {
+        self.paths_between("you", "out")
+    }
+
+    fn part2(&self) -> Result<u64> {
+        self.paths_between_including("svr", "out", &vec!["dac", "fft"])
+    }
+}
+
 impl TryFrom<&Vec<String>> for DirectedGraph {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(value: &Vec<String>) -> Result<Self, Self::Error> {
+    fn try_from(value: &Vec<String>) -> Result<Self> {
         let mut nodes = Vec::new();
         let mut edges = Vec::new();
 
-        for line in value {
+        for (index, line) in value.iter().enumerate() {
             let (node, edges_string) = line.split_once(": ")
-                .ok_or_else(|| format!("Invalid line: {}", line))?;
+                .ok_or_else(|| Error::parse(1, format!("Invalid line: {}", line)).offset(index, 0))?;
 
             let node = node.trim();
 
@@ -217,7 +236,7 @@ impl TryFrom<&Vec<String>> for DirectedGraph {
         }
 
         if !nodes.iter().all_unique() {
-            return Err("Nodes must be unique".to_string());
+            return Err(Error::validation("Nodes must be unique"));
         }
 
         Ok(Self::new(nodes, edges))
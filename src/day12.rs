@@ -1,33 +1,201 @@
-use cp_sat::builder::{BoolVar, CpModelBuilder, LinearExpr};
-use cp_sat::proto::CpSolverStatus;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use itertools::Itertools;
-
-#[derive(Debug)]
+use crate::{Error, Result};
+use crate::solution::{NoAnswer, Solution};
+
+mod backtracking;
+#[cfg(feature = "cp-sat")]
+mod cp_sat_solver;
+mod generator;
+mod parser;
+mod shape_library;
+
+pub use backtracking::BacktrackingSolver;
+#[cfg(feature = "cp-sat")]
+pub use cp_sat_solver::CpSatSolver;
+pub use generator::{GeneratedPuzzle, GroundTruth, PuzzleGenerator};
+pub use shape_library::{InternedShape, Orientation, ShapeLibrary, SymmetryGroup};
+
+use parser::PuzzleParser;
+
+// CP-SAT when it's compiled in, since it copes better with tightly packed regions
+#[cfg(feature = "cp-sat")]
+pub type DefaultSolver = CpSatSolver;
+#[cfg(not(feature = "cp-sat"))]
+pub type DefaultSolver = BacktrackingSolver;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TreeFarm {
+    // as drawn in the input, so they're written back out the same way
     shapes: Vec<Shape>,
+    // the index each shape's header gave it; they only skip numbers if the input did
+    shape_indices: Vec<usize>,
+    library: ShapeLibrary,
     regions: Vec<Region>,
     shape_counts_by_region: Vec<Vec<usize>>,
 }
 
 impl TreeFarm {
-    fn new(
+    pub fn new(
         shapes: Vec<Shape>,
         regions: Vec<Region>,
         shape_counts_by_region: Vec<Vec<usize>>,
-    ) -> Result<Self, String> {
+    ) -> Result<Self> {
         Self::validate_dimensions(&shapes, &regions, &shape_counts_by_region)?;
 
         Ok(Self {
+            library: ShapeLibrary::new(&shapes)?,
+            shape_indices: (0..shapes.len()).collect(),
             shapes,
             regions,
             shape_counts_by_region,
         })
     }
 
-    pub fn valid_regions(&self) -> usize {
-        self.problems()
-            .filter(|problem| problem.has_solution())
-            .count()
+    // for inputs that number their shapes with gaps, one index per shape
+    fn with_shape_indices(self, shape_indices: Vec<usize>) -> Self {
+        Self { shape_indices, ..self }
+    }
+
+    pub fn shape_library(&self) -> &ShapeLibrary {
+        &self.library
+    }
+
+    // the header index of each shape, by position
+    pub fn shape_indices(&self) -> &[usize] {
+        &self.shape_indices
+    }
+
+    pub fn valid_regions(&self) -> Result<usize> {
+        self.valid_regions_with(&DefaultSolver::default(), &SolveOptions::default(), &mut |_: &RegionReport| {})
+    }
+
+    pub fn valid_regions_with(
+        &self,
+        solver: &(impl PackingSolver + ?Sized),
+        options: &SolveOptions,
+        observer: &mut impl RegionObserver,
+    ) -> Result<usize> {
+        let count = self.region_layouts_with(solver, options, observer)?
+            .iter()
+            .filter(|layout| layout.is_packable())
+            .count();
+
+        Ok(count)
+    }
+
+    pub fn region_layouts(&self) -> Result<Vec<RegionLayout>> {
+        self.region_layouts_with(&DefaultSolver::default(), &SolveOptions::default(), &mut |_: &RegionReport| {})
+    }
+
+    // layouts come back in region order however many threads solve them
+    pub fn region_layouts_with(
+        &self,
+        solver: &(impl PackingSolver + ?Sized),
+        options: &SolveOptions,
+        observer: &mut impl RegionObserver,
+    ) -> Result<Vec<RegionLayout>> {
+        let problems: Vec<RegionProblem> = self.problems().collect();
+        let observer = Mutex::new(observer);
+
+        let solve = |(index, problem): (usize, &RegionProblem)| {
+            let start = Instant::now();
+            let deadline = options.timeout.map(|timeout| start + timeout);
+            let (outcome, placements) = problem.solution(solver, deadline);
+
+            let report = RegionReport { index, outcome, elapsed: start.elapsed() };
+            observer.lock().unwrap().observe(&report);
+
+            RegionLayout { region: index, outcome, placements }
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let solve_all = || problems.par_iter().enumerate().map(solve).collect();
+
+            match options.threads {
+                Some(threads) => Ok(rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|e| Error::solver(format!("Failed to start {} threads: {}", threads, e)))?
+                    .install(solve_all)),
+                None => Ok(solve_all()),
+            }
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        Ok(problems.iter().enumerate().map(solve).collect())
+    }
+
+    pub fn maximize(&self, region: usize, objective: Objective) -> Result<RegionOptimum> {
+        self.maximize_with(region, objective, &DefaultSolver::default(), &SolveOptions::default())
+    }
+
+    // treats the region's shape counts as upper bounds; `options.threads` doesn't apply
+    pub fn maximize_with(
+        &self,
+        region: usize,
+        objective: Objective,
+        solver: &(impl PackingSolver + ?Sized),
+        options: &SolveOptions,
+    ) -> Result<RegionOptimum> {
+        let problem = self.problems()
+            .nth(region)
+            .ok_or_else(|| Error::validation(format!("Region {} does not exist", region)))?;
+
+        let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+        let (placements, optimal) = problem.maximize(solver, objective, deadline);
+
+        let presents = placements.len();
+        let cells = placements
+            .iter()
+            .filter_map(|placement| self.library.shape(placement.shape))
+            .map(InternedShape::cell_count)
+            .sum();
+
+        Ok(RegionOptimum { region, objective, placements, presents, cells, optimal })
+    }
+
+    // the region cells a placement covers, or `None` if it names a shape or orientation that
+    // doesn't exist
+    pub fn covered_coordinates(&self, placement: &PresentPlacement) -> Option<Vec<(usize, usize)>> {
+        let orientation = self.library
+            .shape(placement.shape)?
+            .orientations()
+            .get(placement.orientation)?;
+
+        let coordinates = orientation
+            .covered_coordinates()
+            .map(|(x, y)| (x + placement.x, y + placement.y))
+            .collect();
+
+        Some(coordinates)
+    }
+
+    pub fn packing(&self, layout: &RegionLayout) -> Result<Packing> {
+        self.packing_of(layout.region, layout.placements.as_deref().unwrap_or_default())
+    }
+
+    pub fn packing_of(&self, region: usize, placements: &[PresentPlacement]) -> Result<Packing> {
+        let region = self.regions.get(region).ok_or_else(|| {
+            Error::validation(format!("Region {} does not exist", region))
+        })?;
+
+        let mut packing = Packing::new(region);
+
+        for (index, placement) in placements.iter().enumerate() {
+            let coordinates = self.covered_coordinates(placement).ok_or_else(|| {
+                Error::validation(format!("Invalid placement: {:?}", placement))
+            })?;
+
+            packing.place(index, &coordinates)?;
+        }
+
+        Ok(packing)
     }
 
     fn problems<'a>(&'a self) -> impl Iterator<Item = RegionProblem<'a>> {
@@ -35,7 +203,7 @@ impl TreeFarm {
             .iter()
             .zip(self.shape_counts_by_region.iter())
             .map(|(region, shape_counts)| {
-                RegionProblem::new(&self.shapes, region, shape_counts)
+                RegionProblem::new(&self.library, region, shape_counts)
             })
     }
 
@@ -43,25 +211,25 @@ impl TreeFarm {
         shapes: &[Shape],
         regions: &[Region],
         shape_counts_by_region: &[Vec<usize>],
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         if regions.len() != shape_counts_by_region.len() {
-            return Err(format!(
+            return Err(Error::validation(format!(
                 "Mismatch: {} regions but {} shape-count rows",
                 regions.len(),
                 shape_counts_by_region.len()
-            ));
+            )));
         }
 
         let shape_count = shapes.len();
 
         for (i, counts) in shape_counts_by_region.iter().enumerate() {
             if counts.len() != shape_count {
-                return Err(format!(
+                return Err(Error::validation(format!(
                     "Region {}: expected {} shape counts, got {}",
                     i,
                     shape_count,
                     counts.len()
-                ));
+                )));
             }
         }
 
@@ -69,280 +237,621 @@ impl TreeFarm {
     }
 }
 
-impl TryFrom<&Vec<String>> for TreeFarm {
-    type Error = String;
+impl Solution for TreeFarm {
+    type Part1 = usize;
+    type Part2 = NoAnswer;
 
-    fn try_from(lines: &Vec<String>) -> Result<Self, Self::Error> {
-        let mut shapes = Vec::new();
-        let mut regions = Vec::new();
-        let mut shape_counts_by_region: Vec<Vec<usize>> = Vec::new();
+    fn parse(input: &[String]) -> Result<Self> {
+        Self::try_from(&input.to_vec())
+    }
 
-        let mut index: usize = 0;
+    fn part1(&self) -> Result<usize> {
+        self.valid_regions()
+    }
 
-        while index < lines.len() {
-            let line = lines[index].trim();
+    fn part2(&self) -> Result<NoAnswer> {
+        Ok(NoAnswer)
+    }
+}
 
-            // shape header, e.g. "0:"
-            if line.ends_with(':') && line[..line.len() - 1].parse::<usize>().is_ok() {
-                index += 1;
-                let start = index;
+impl TryFrom<&Vec<String>> for TreeFarm {
+    type Error = Error;
 
-                // collect grid lines
-                while index < lines.len() {
-                    let grid_line = lines[index].trim();
+    fn try_from(lines: &Vec<String>) -> Result<Self> {
+        PuzzleParser::new(lines).parse()
+    }
+}
 
-                    if grid_line.is_empty() {
-                        break;
-                    }
+// the puzzle format `TreeFarm::try_from` reads: numbered shapes, each followed by a blank
+// line, then one line per region
+impl fmt::Display for TreeFarm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, shape) in self.shape_indices.iter().zip(self.shapes.iter()) {
+            writeln!(f, "{}:\n{}", index, shape)?;
+        }
 
-                    // stop if we hit a region line
-                    if grid_line.contains('x') && grid_line.contains(':') {
-                        break;
-                    }
+        for (region, shape_counts) in self.regions.iter().zip(self.shape_counts_by_region.iter()) {
+            writeln!(f, "{}: {}", region, shape_counts.iter().join(" "))?;
+        }
 
-                    index += 1;
-                }
+        Ok(())
+    }
+}
 
-                if index > start {
-                    let grid_lines = &lines[start..index];
-                    shapes.push(Shape::try_from(grid_lines)?);
-                }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolveOptions {
+    // worker threads, with the `parallel` feature; `None` leaves it to rayon. Without the
+    // feature regions are solved one at a time on the calling thread, whatever this says
+    pub threads: Option<usize>,
+    // how long each region may take before it's reported as `RegionOutcome::Unknown`
+    pub timeout: Option<Duration>,
+}
 
-                // skip blank line if present
-                if index < lines.len() && lines[index].trim().is_empty() {
-                    index += 1;
-                }
+// a backend that decides whether (and how) a region can be packed; shared between threads
+// when regions are solved in parallel
+pub trait PackingSolver: Sync {
+    // `shape_counts[shape]` non-overlapping placements must be picked from
+    // `placements_by_shape[shape]`, giving up once `deadline` passes
+    fn pack(&self, problem: &PackingProblem, deadline: Option<Instant>) -> PackResult;
 
-                continue;
-            }
+    // picks at most `shape_counts[shape]` placements of each shape, maximising the summed
+    // `weights[shape]` of the picks; settles for the best found once `deadline` passes
+    fn maximize(&self, problem: &PackingProblem, weights: &[usize], deadline: Option<Instant>) -> PartialPacking;
+}
 
-            // region line, e.g. "10x8: 2 0 4 ..."
-            if line.contains('x') && line.contains(':') {
-                let (_, counts_str) = line
-                    .split_once(':')
-                    .ok_or_else(|| format!("Invalid region line: {}", line))?;
-
-                let shape_counts: Vec<usize> = counts_str
-                    .trim()
-                    .split_whitespace()
-                    .map(|s| s.parse::<usize>().map_err(|e| format!("Invalid count: {}", e)))
-                    .collect::<Result<_, String>>()?;
-
-                let region = Region::try_from(line)?;
-                regions.push(region);
-                shape_counts_by_region.push(shape_counts);
-                index += 1;
-                continue;
-            }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialPacking {
+    pub chosen_by_shape: Vec<Vec<usize>>,
+    // false if the solver ran out of time before proving nothing better exists
+    pub optimal: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    Presents,
+    Cells,
+}
+
+impl TryFrom<&str> for Objective {
+    type Error = Error;
 
-            index += 1;
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "presents" => Ok(Objective::Presents),
+            "cells" => Ok(Objective::Cells),
+            _ => Err(Error::validation(format!("Unknown objective '{}'", value))),
         }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionOptimum {
+    pub region: usize,
+    pub objective: Objective,
+    pub placements: Vec<PresentPlacement>,
+    pub presents: usize,
+    pub cells: usize,
+    pub optimal: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackResult {
+    // the picked placement indices for each shape
+    Packed(Vec<Vec<usize>>),
+    Unpackable,
+    TimedOut,
+}
+
+// cells are numbered y * width + x within a `width` x `height` region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackingProblem {
+    pub width: usize,
+    pub height: usize,
+    pub shape_counts: Vec<usize>,
+    // the sorted cells covered by each candidate placement
+    pub placements_by_shape: Vec<Vec<Vec<usize>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionLayout {
+    pub region: usize,
+    pub outcome: RegionOutcome,
+    // `None` unless the presents were all packed into the region
+    pub placements: Option<Vec<PresentPlacement>>,
+}
+
+impl RegionLayout {
+    pub fn is_packable(&self) -> bool {
+        self.placements.is_some()
+    }
+}
+
+// `orientation` indexes the shape's orientations in the `ShapeLibrary`, which for a duplicate
+// are those of the first shape like it; (x, y) is the top-left of the oriented grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PresentPlacement {
+    pub shape: usize,
+    pub orientation: usize,
+    pub x: usize,
+    pub y: usize,
+}
+
+// notified once per region as the regions are solved; in input order unless they're
+// solved in parallel
+pub trait RegionObserver: Send {
+    fn observe(&mut self, report: &RegionReport);
+}
 
-        Self::new(shapes, regions, shape_counts_by_region)
+impl<F: FnMut(&RegionReport) + Send> RegionObserver for F {
+    fn observe(&mut self, report: &RegionReport) {
+        self(report)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionReport {
+    pub index: usize,
+    pub outcome: RegionOutcome,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionOutcome {
+    // settled by a pre-check, without running the solver:
+    // the presents cover more cells than the region has
+    NoCapacity,
+    // some present doesn't fit in the region in any orientation
+    NoPlacement,
+    // every present gets its own bounding box
+    TriviallyFits,
+    // the presents can't be split between the black and white cells of a checkerboard
+    ColoringMismatch,
+    // settled by the solver
+    Feasible,
+    Infeasible,
+    // the solver ran out of time
+    Unknown,
+}
+
+impl fmt::Display for RegionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionOutcome::NoCapacity => write!(f, "no capacity"),
+            RegionOutcome::NoPlacement => write!(f, "no placement"),
+            RegionOutcome::TriviallyFits => write!(f, "trivially fits"),
+            RegionOutcome::ColoringMismatch => write!(f, "coloring mismatch"),
+            RegionOutcome::Feasible => write!(f, "feasible"),
+            RegionOutcome::Infeasible => write!(f, "infeasible"),
+            RegionOutcome::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+// what a pre-check can tell about a region without running a solver
+enum Verdict {
+    Fits(Vec<PresentPlacement>),
+    DoesNotFit,
+    Unknown,
+}
+
+type PreCheck<'a> = fn(&RegionProblem<'a>) -> Verdict;
+
 struct RegionProblem<'a> {
-    shapes: &'a [Shape],
+    library: &'a ShapeLibrary,
     region: &'a Region,
     shape_counts: &'a [usize],
-    placements_by_shape: Vec<Vec<Placement>>,
 }
 
 impl<'a> RegionProblem<'a> {
-    fn sum(vars: &[BoolVar]) -> LinearExpr {
-        vars.iter().fold(0.into(), |acc: LinearExpr, &v| acc + v)
-    }
+    // cheapest first; the first definite verdict settles the region
+    const PRE_CHECKS: [(RegionOutcome, PreCheck<'a>); 4] = [
+        (RegionOutcome::NoCapacity, Self::capacity_check),
+        (RegionOutcome::NoPlacement, Self::placement_check),
+        (RegionOutcome::TriviallyFits, Self::bounding_box_check),
+        (RegionOutcome::ColoringMismatch, Self::coloring_check),
+    ];
 
     fn new(
-        shapes: &'a [Shape],
+        library: &'a ShapeLibrary,
         region: &'a Region,
         shape_counts: &'a [usize],
     ) -> Self {
-        let placements_by_shape = Self::placements_by_shape(shapes, region);
-        Self { shapes, region, shape_counts, placements_by_shape }
+        Self { library, region, shape_counts }
     }
 
-    pub fn has_solution(&self) -> bool {
-        if !self.has_capacity() {
-            println!("No capacity - skipping");
-            return false;
+    fn solution(
+        &self,
+        solver: &(impl PackingSolver + ?Sized),
+        deadline: Option<Instant>,
+    ) -> (RegionOutcome, Option<Vec<PresentPlacement>>) {
+        for (outcome, check) in Self::PRE_CHECKS {
+            match check(self) {
+                Verdict::Fits(placements) => return (outcome, Some(placements)),
+                Verdict::DoesNotFit => return (outcome, None),
+                Verdict::Unknown => {}
+            }
         }
 
-        println!("Solving");
-        self.solve()
+        let placements_by_shape = Self::placements_by_shape(self.library, self.region);
+
+        match solver.pack(&self.packing_problem(&placements_by_shape), deadline) {
+            PackResult::Packed(chosen_by_shape) => {
+                (RegionOutcome::Feasible, Some(Self::present_placements(&placements_by_shape, &chosen_by_shape)))
+            }
+            PackResult::Unpackable => (RegionOutcome::Infeasible, None),
+            PackResult::TimedOut => (RegionOutcome::Unknown, None),
+        }
     }
 
-    fn solve(&self) -> bool {
-        let mut model = CpModelBuilder::default();
-        let placement_vars_by_shape = self.placement_vars_by_shape(&mut model);
+    // the placements found, and whether they're known to be the best possible
+    fn maximize(
+        &self,
+        solver: &(impl PackingSolver + ?Sized),
+        objective: Objective,
+        deadline: Option<Instant>,
+    ) -> (Vec<PresentPlacement>, bool) {
+        if let Verdict::Fits(placements) = self.bounding_box_check() {
+            return (placements, true);
+        }
+
+        let weights: Vec<usize> = self.library
+            .shapes()
+            .map(|shape| match objective {
+                Objective::Presents => 1,
+                Objective::Cells => shape.cell_count(),
+            })
+            .collect();
 
-        self.add_shape_count_constraints(&mut model, &placement_vars_by_shape);
-        self.add_overlap_constraints(&mut model, &placement_vars_by_shape);
+        let placements_by_shape = Self::placements_by_shape(self.library, self.region);
+        let partial = solver.maximize(&self.packing_problem(&placements_by_shape), &weights, deadline);
 
-        let response = model.solve();
-        matches!(response.status(), CpSolverStatus::Optimal | CpSolverStatus::Feasible)
+        (Self::present_placements(&placements_by_shape, &partial.chosen_by_shape), partial.optimal)
     }
 
-    fn required_cell_count(&self) -> usize {
-        self.shapes
+    fn present_placements(placements_by_shape: &[Vec<Placement>], chosen_by_shape: &[Vec<usize>]) -> Vec<PresentPlacement> {
+        placements_by_shape
             .iter()
+            .zip(chosen_by_shape.iter())
+            .flat_map(|(placements, chosen)| chosen.iter().map(|&index| placements[index].present_placement()))
+            .collect()
+    }
+
+    fn packing_problem(&self, placements_by_shape: &[Vec<Placement>]) -> PackingProblem {
+        PackingProblem {
+            width: self.region.width,
+            height: self.region.height,
+            shape_counts: self.shape_counts.to_vec(),
+            placements_by_shape: placements_by_shape
+                .iter()
+                .map(|placements| placements.iter().map(|placement| placement.cell_indices(self.region.width)).collect())
+                .collect(),
+        }
+    }
+
+    fn required_cell_count(&self) -> usize {
+        self.library
+            .shapes()
             .zip(self.shape_counts.iter())
-            .map(|(shape, &count)| shape.covered_cell_count() * count)
+            .map(|(shape, &count)| shape.cell_count() * count)
             .sum()
     }
 
-    fn has_capacity(&self) -> bool {
-        self.region.cell_count() >= self.required_cell_count()
+    fn used_shapes(&self) -> impl Iterator<Item = (usize, &'a InternedShape, usize)> {
+        self.library
+            .shapes()
+            .zip(self.shape_counts.iter())
+            .enumerate()
+            .filter(|(_, (_, count))| **count > 0)
+            .map(|(index, (shape, &count))| (index, shape, count))
     }
 
-    fn add_shape_count_constraints(
-        &self,
-        model: &mut CpModelBuilder,
-        placement_vars_by_shape: &[Vec<BoolVar>],
-    ) {
-        for (vars, &count) in placement_vars_by_shape.iter().zip(self.shape_counts.iter()) {
-            let sum = Self::sum(vars);
-            model.add_eq(sum, count as i64);
+    fn capacity_check(&self) -> Verdict {
+        if self.region.cell_count() < self.required_cell_count() {
+            return Verdict::DoesNotFit;
         }
+
+        Verdict::Unknown
     }
 
-    fn add_overlap_constraints(
-        &self,
-        model: &mut CpModelBuilder,
-        placement_vars_by_shape: &[Vec<BoolVar>],
-    ) {
-        let placement_vars_by_coordinate = self.placement_vars_by_coordinate(placement_vars_by_shape);
+    fn placement_check(&self) -> Verdict {
+        let unplaceable = self.used_shapes().any(|(_, shape, _)| {
+            shape.orientations()
+                .iter()
+                .all(|orientation| orientation.width() > self.region.width || orientation.height() > self.region.height)
+        });
 
-        for (x, y) in self.region.coordinates() {
-            let placement_vars = &placement_vars_by_coordinate[x][y];
+        if unplaceable {
+            return Verdict::DoesNotFit;
+        }
 
-            if placement_vars.is_empty() {
-                continue;
-            }
+        Verdict::Unknown
+    }
+
+    // tiles the region with boxes big enough for any of the presents, one present per box
+    fn bounding_box_check(&self) -> Verdict {
+        let box_width = self.used_shapes().map(|(_, shape, _)| shape.orientations()[0].width()).max().unwrap_or(1);
+        let box_height = self.used_shapes().map(|(_, shape, _)| shape.orientations()[0].height()).max().unwrap_or(1);
 
-            let coordinate_expression = Self::sum(placement_vars);
+        let columns = self.region.width / box_width;
+        let box_count = columns * (self.region.height / box_height);
+        let present_count: usize = self.shape_counts.iter().sum();
 
-            model.add_le(coordinate_expression, 1);
+        if box_count < present_count {
+            return Verdict::Unknown;
         }
-    }
 
-    fn placement_vars_by_shape(&self, model: &mut CpModelBuilder) -> Vec<Vec<BoolVar>> {
-        self.placements_by_shape
-            .iter()
-            .map(|placements| placements.iter().map(|_| model.new_bool_var()).collect())
-            .collect()
+        let placements = self.used_shapes()
+            .flat_map(|(index, _, count)| std::iter::repeat_n(index, count))
+            .enumerate()
+            .map(|(slot, shape)| PresentPlacement {
+                shape,
+                orientation: 0,
+                x: (slot % columns) * box_width,
+                y: (slot / columns) * box_height,
+            })
+            .collect();
+
+        Verdict::Fits(placements)
     }
 
-    fn placement_vars_by_coordinate(
-        &self,
-        placement_vars_by_shape: &[Vec<BoolVar>],
-    ) -> Vec<Vec<Vec<BoolVar>>> {
-        self.placements_by_shape
-            .iter()
-            .zip(placement_vars_by_shape.iter())
-            .flat_map(|(placements, vars)| placements.iter().zip(vars.iter()))
-            .fold(
-                vec![vec![Vec::new(); self.region.height]; self.region.width],
-                |mut result, (placement, &var)| {
-                    for (x, y) in placement.covered_coordinates() {
-                        result[x][y].push(var);
+    // every placement of a shape covers the same split of checkerboard colours, or its
+    // reverse, so the imbalances have to be signed to fit the region's black and white cells
+    fn coloring_check(&self) -> Verdict {
+        let required = self.required_cell_count() as i64;
+        let black = self.region.cell_count().div_ceil(2) as i64;
+        let white = (self.region.cell_count() / 2) as i64;
+
+        // the signed imbalance (black minus white) the presents can take, overall
+        let (low, high) = (required - 2 * white, 2 * black - required);
+
+        let imbalances: Vec<(i64, usize)> = self.used_shapes()
+            .map(|(_, shape, count)| (shape.color_imbalance() as i64, count))
+            .collect();
+
+        let total: i64 = imbalances.iter().map(|&(imbalance, count)| imbalance * count as i64).sum();
+
+        if low <= -total && total <= high {
+            return Verdict::Unknown;
+        }
+
+        // reachable[i] means a signed imbalance of i - total is possible
+        let mut reachable = vec![false; 2 * total as usize + 1];
+        reachable[total as usize] = true;
+
+        for (imbalance, count) in imbalances {
+            for _ in 0..count {
+                let mut next = vec![false; reachable.len()];
+
+                for (i, _) in reachable.iter().enumerate().filter(|(_, reached)| **reached) {
+                    let i = i as i64;
+
+                    for j in [i - imbalance, i + imbalance] {
+                        if (0..next.len() as i64).contains(&j) {
+                            next[j as usize] = true;
+                        }
                     }
+                }
 
-                    result
-                },
-            )
-    }
+                reachable = next;
+            }
+        }
 
-    fn placements_by_shape(shapes: &[Shape], region: &Region) -> Vec<Vec<Placement>> {
-        shapes
+        let fits = reachable
             .iter()
-            .map(|shape| Self::placements(shape, region))
+            .enumerate()
+            .any(|(i, &reached)| reached && (low..=high).contains(&(i as i64 - total)));
+
+        if fits {
+            return Verdict::Unknown;
+        }
+
+        Verdict::DoesNotFit
+    }
+
+    fn placements_by_shape(library: &'a ShapeLibrary, region: &Region) -> Vec<Vec<Placement<'a>>> {
+        library
+            .shapes()
+            .enumerate()
+            .map(|(shape_index, shape)| Self::placements(shape_index, shape, region))
             .collect()
     }
 
-    fn placements(shape: &Shape, region: &Region) -> Vec<Placement> {
+    fn placements(shape_index: usize, shape: &'a InternedShape, region: &Region) -> Vec<Placement<'a>> {
         shape.orientations()
-            .into_iter()
-            .flat_map(|orientation| {
-                Self::coordinate_placements(&orientation, region)
+            .iter()
+            .enumerate()
+            .flat_map(|(orientation_index, orientation)| {
+                Self::coordinate_placements(orientation, region)
                     .into_iter()
-                    .map(move |(x, y)| Placement::new(orientation.clone(), x, y))
+                    .map(move |(x, y)| Placement::new(shape_index, orientation_index, orientation, x, y))
             })
             .collect()
     }
 
-    fn coordinate_placements(shape: &Shape, region: &Region) -> Vec<(usize, usize)> {
-        if shape.width() > region.width || shape.height() > region.height {
+    fn coordinate_placements(orientation: &Orientation, region: &Region) -> Vec<(usize, usize)> {
+        if orientation.width() > region.width || orientation.height() > region.height {
             return Vec::new();
         }
 
-        (0..=region.width - shape.width())
-            .cartesian_product(0..=region.height - shape.height())
+        (0..=region.width - orientation.width())
+            .cartesian_product(0..=region.height - orientation.height())
             .collect()
     }
 }
 
-#[derive(Debug)]
-struct Region {
+// which placement (by its position in the layout) covers each cell of a region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packing {
+    cells: Vec<Vec<Option<usize>>>,
+}
+
+impl Packing {
+    const LETTERS: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    const SVG_CELL_SIZE: usize = 20;
+
+    fn new(region: &Region) -> Self {
+        Self { cells: vec![vec![None; region.width]; region.height] }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.first().map_or(0, |row| row.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn place(&mut self, index: usize, coordinates: &[(usize, usize)]) -> Result<()> {
+        for &(x, y) in coordinates {
+            let cell = self.cells
+                .get_mut(y)
+                .and_then(|row| row.get_mut(x))
+                .ok_or_else(|| Error::validation(format!("Placement {} leaves the region at ({}, {})", index, x, y)))?;
+
+            if let Some(other) = cell {
+                return Err(Error::validation(format!(
+                    "Placements {} and {} overlap at ({}, {})", other, index, x, y
+                )));
+            }
+
+            *cell = Some(index);
+        }
+
+        Ok(())
+    }
+
+    // letters repeat after 52 placements
+    fn letter(index: usize) -> char {
+        Self::LETTERS[index % Self::LETTERS.len()] as char
+    }
+
+    // spreads neighbouring placements around the colour wheel
+    fn hue(index: usize) -> usize {
+        (index * 137) % 360
+    }
+
+    pub fn to_svg(&self) -> String {
+        let size = Self::SVG_CELL_SIZE;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            self.width() * size,
+            self.height() * size,
+            self.width() * size,
+            self.height() * size,
+        );
+
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let fill = match cell {
+                    Some(index) => format!("hsl({}, 70%, 60%)", Self::hue(*index)),
+                    None => "white".to_string(),
+                };
+
+                svg.push_str(&format!(
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#999\"/>\n",
+                    x * size, y * size, size, size, fill,
+                ));
+
+                if let Some(index) = cell {
+                    svg.push_str(&format!(
+                        "  <text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                        x * size + size / 2, y * size + size / 2, size * 3 / 5, Self::letter(*index),
+                    ));
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl fmt::Display for Packing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.iter() {
+            let line: String = row.iter()
+                .map(|cell| cell.map_or('.', Self::letter))
+                .collect();
+
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
     width: usize,
     height: usize,
 }
 
 impl Region {
-    fn cell_count(&self) -> usize {
-        self.width * self.height
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
     }
 
-    fn coordinates(&self) -> impl Iterator<Item = (usize, usize)> {
-        (0..self.width).cartesian_product(0..self.height)
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn cell_count(&self) -> usize {
+        self.width * self.height
     }
 }
 
+// just the dimensions, e.g. "12x5"; the shape counts belong to the `TreeFarm`
 impl TryFrom<&str> for Region {
-    type Error = String;
-
-    fn try_from(line: &str) -> Result<Self, Self::Error> {
-        let (dimensions, _) = line
-            .split_once(':')
-            .ok_or_else(|| format!("Invalid region line: {}", line))?;
+    type Error = Error;
 
+    fn try_from(dimensions: &str) -> Result<Self> {
         let (width_str, height_str) = dimensions
             .split_once('x')
-            .ok_or_else(|| format!("Invalid dimensions format: {}", dimensions))?;
+            .ok_or_else(|| Error::parse(1, format!("Invalid dimensions format: {}", dimensions)))?;
 
         let width = width_str
             .trim()
             .parse::<usize>()
-            .map_err(|e| format!("Invalid width: {}", e))?;
+            .map_err(|e| Error::parse(1, format!("Invalid width: {}", e)))?;
 
         let height = height_str
             .trim()
             .parse::<usize>()
-            .map_err(|e| format!("Invalid height: {}", e))?;
+            .map_err(|e| Error::parse(width_str.len() + 2, format!("Invalid height: {}", e)))?;
 
         Ok(Self { width, height })
     }
 }
 
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Shape {
+pub struct Shape {
     grid: Vec<Vec<GridState>>,
     covered_coordinates: Vec<(usize, usize)>,
 }
 
 impl Shape {
-    fn new(grid: Vec<Vec<GridState>>) -> Result<Self, String> {
+    fn new(grid: Vec<Vec<GridState>>) -> Result<Self> {
         if grid.is_empty() {
-            return Err("Grid cannot be empty".to_string());
+            return Err(Error::validation("Grid cannot be empty"));
         }
 
         if !Self::all_same_size(&grid) {
-            return Err("All rows must have the same size".to_string());
+            return Err(Error::validation("All rows must have the same size"));
         }
 
         if Self::degenerate(&grid) {
-            return Err("Grid cannot have empty rows or columns".to_string());
+            return Err(Error::validation("Grid cannot have empty rows or columns"));
         }
 
         let covered_coordinates = Self::compute_covered_coordinates(&grid);
@@ -360,15 +869,6 @@ impl Shape {
         self.grid.len()
     }
 
-    pub fn orientations(&self) -> Vec<Self> {
-        // flips included
-        vec![self.clone(), self.flip()]
-            .into_iter()
-            .flat_map(|shape| (0..4).map(move |i| shape.rotate(i)))
-            .unique()
-            .collect()
-    }
-
     fn compute_covered_coordinates(grid: &[Vec<GridState>]) -> Vec<(usize, usize)> {
         grid.iter()
             .enumerate()
@@ -387,39 +887,6 @@ impl Shape {
         &self.covered_coordinates
     }
 
-    fn covered_cell_count(&self) -> usize {
-        self.covered_coordinates.len()
-    }
-
-    fn rotate(&self, count: u32) -> Self {
-        (0..count).fold(self.clone(), |result, _| result.rotate_once())
-    }
-
-    fn flip(&self) -> Self {
-        let flipped: Vec<Vec<GridState>> = self
-            .grid
-            .iter()
-            .map(|row| row.iter().rev().copied().collect())
-            .collect();
-
-        Self::new(flipped).unwrap()
-    }
-
-    fn rotate_once(&self) -> Self {
-        let height = self.height();
-        let width = self.width();
-
-        let mut rotated = vec![vec![GridState::Empty; height]; width];
-
-        for (y, row) in self.grid.iter().enumerate() {
-            for (x, &cell) in row.iter().enumerate() {
-                rotated[width - 1 - x][y] = cell;
-            }
-        }
-
-        Self::new(rotated).unwrap()
-    }
-
     fn all_same_size<T>(items: &[Vec<T>]) -> bool {
         if items.is_empty() {
             return true;
@@ -443,24 +910,37 @@ impl Shape {
 }
 
 impl TryFrom<&[String]> for Shape {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(lines: &[String]) -> Result<Self, Self::Error> {
+    fn try_from(lines: &[String]) -> Result<Self> {
         let grid: Vec<Vec<GridState>> = lines
             .iter()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| {
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(y, line)| {
                 line.trim()
                     .chars()
-                    .map(GridState::try_from)
-                    .collect::<Result<_, String>>()
+                    .enumerate()
+                    .map(|(x, c)| GridState::try_from(c).map_err(|e| e.offset(y, x)))
+                    .collect::<Result<_>>()
             })
-            .collect::<Result<_, String>>()?;
+            .collect::<Result<_>>()?;
 
         Self::new(grid)
     }
 }
 
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.grid.iter() {
+            let line: String = row.iter().map(|&cell| char::from(cell)).collect();
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum GridState {
     Empty,
@@ -468,33 +948,147 @@ enum GridState {
 }
 
 impl TryFrom<char> for GridState {
-    type Error = String;
+    type Error = Error;
 
-    fn try_from(c: char) -> Result<Self, Self::Error> {
+    fn try_from(c: char) -> Result<Self> {
         match c {
             '#' => Ok(GridState::Present),
             '.' => Ok(GridState::Empty),
-            _ => Err(format!("Invalid character in grid: {}", c)),
+            _ => Err(Error::parse(1, format!("Invalid character in grid: {}", c))),
+        }
+    }
+}
+
+impl From<GridState> for char {
+    fn from(state: GridState) -> Self {
+        match state {
+            GridState::Present => '#',
+            GridState::Empty => '.',
         }
     }
 }
 
-struct Placement {
-    shape: Shape,
+struct Placement<'a> {
+    shape_index: usize,
+    orientation_index: usize,
+    orientation: &'a Orientation,
     x: usize,
     y: usize,
 }
 
-impl Placement {
-    fn new(shape: Shape, x: usize, y: usize) -> Self {
-        Self { shape, x, y }
+impl<'a> Placement<'a> {
+    fn new(shape_index: usize, orientation_index: usize, orientation: &'a Orientation, x: usize, y: usize) -> Self {
+        Self { shape_index, orientation_index, orientation, x, y }
     }
 
-    fn covered_coordinates(&self) -> Vec<(usize, usize)> {
-        self.shape
-            .covered_coordinates()
-            .iter()
-            .map(move |&(x, y)| (x + self.x, y + self.y))
-            .collect()
+    fn present_placement(&self) -> PresentPlacement {
+        PresentPlacement {
+            shape: self.shape_index,
+            orientation: self.orientation_index,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn cell_indices(&self, width: usize) -> Vec<usize> {
+        self.orientation.cell_indices(self.x, self.y, width).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // shared with the submodules' tests
+    pub(super) fn parse(text: &str) -> Result<TreeFarm> {
+        let lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+        TreeFarm::try_from(&lines)
+    }
+
+    pub(super) fn shape(text: &str) -> Shape {
+        let lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+        Shape::try_from(lines.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn reports_regions_without_capacity() {
+        let tree_farm = parse("0:\n##\n##\n\n3x3: 3\n2x2: 2\n").unwrap();
+        let mut outcomes = Vec::new();
+
+        let options = SolveOptions::default();
+
+        let valid_regions = tree_farm
+            .valid_regions_with(&BacktrackingSolver, &options, &mut |report: &RegionReport| {
+                outcomes.push((report.index, report.outcome));
+            })
+            .unwrap();
+
+        assert_eq!(valid_regions, 0);
+        assert_eq!(outcomes, vec![(0, RegionOutcome::NoCapacity), (1, RegionOutcome::NoCapacity)]);
+        assert!(tree_farm.region_layouts().unwrap().iter().all(|layout| !layout.is_packable()));
+    }
+
+    #[test]
+    fn settles_regions_with_pre_checks() {
+        let tree_farm = parse("0:\n###\n.#.\n\n6x4: 4\n2x2: 1\n6x2: 3\n").unwrap();
+        let mut outcomes = Vec::new();
+
+        let layouts = tree_farm
+            .region_layouts_with(&BacktrackingSolver, &SolveOptions::default(), &mut |report: &RegionReport| {
+                outcomes.push(report.outcome);
+            })
+            .unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![RegionOutcome::TriviallyFits, RegionOutcome::NoPlacement, RegionOutcome::ColoringMismatch]
+        );
+
+        assert_eq!(tree_farm.packing(&layouts[0]).unwrap().to_string(), "AAABBB\n.A..B.\nCCCDDD\n.C..D.\n");
+    }
+
+    #[test]
+    fn maximizes_presents_in_regions_that_cannot_fit_them_all() {
+        let tree_farm = parse("0:\n###\n.#.\n\n6x2: 3\n").unwrap();
+        let optimum = tree_farm
+            .maximize_with(0, Objective::Cells, &BacktrackingSolver, &SolveOptions::default())
+            .unwrap();
+
+        assert_eq!((optimum.presents, optimum.cells, optimum.optimal), (2, 8, true));
+        assert!(tree_farm.packing_of(0, &optimum.placements).is_ok());
+    }
+
+    #[test]
+    fn maps_placements_onto_region_cells() {
+        let tree_farm = parse("0:\n##\n#.\n\n4x4: 1\n").unwrap();
+        let placement = |orientation| PresentPlacement { shape: 0, orientation, x: 1, y: 2 };
+
+        assert_eq!(tree_farm.covered_coordinates(&placement(0)), Some(vec![(1, 2), (2, 2), (1, 3)]));
+        assert_eq!(tree_farm.covered_coordinates(&placement(1)), Some(vec![(1, 2), (1, 3), (2, 3)]));
+        assert_eq!(tree_farm.covered_coordinates(&placement(4)), None);
+    }
+
+    #[test]
+    fn renders_packings_as_lettered_grids() {
+        let tree_farm = parse("0:\n##\n#.\n\n3x2: 2\n").unwrap();
+        let placement = |orientation, x| PresentPlacement { shape: 0, orientation, x, y: 0 };
+
+        let layout = |placements| RegionLayout { region: 0, outcome: RegionOutcome::Feasible, placements: Some(placements) };
+        let packed = layout(vec![placement(0, 0), placement(2, 1)]);
+        assert_eq!(tree_farm.packing(&packed).unwrap().to_string(), "AAB\nABB\n");
+
+        let overlapping = layout(vec![placement(0, 0), placement(0, 0)]);
+        assert!(tree_farm.packing(&overlapping).is_err());
+    }
+
+    #[test]
+    fn writes_the_puzzle_format_back_out() {
+        let text = "0:\n###\n.#.\n\n1:\n#.\n##\n\n6x4: 4 0\n12x5: 1 3\n";
+        let tree_farm = parse(text).unwrap();
+        assert_eq!(tree_farm.to_string(), text);
+        assert_eq!(parse(&tree_farm.to_string()).unwrap(), tree_farm);
+
+        assert_eq!(Region::try_from("12x5").unwrap(), Region::new(12, 5));
+        assert_eq!(Region::new(12, 5).to_string(), "12x5");
     }
 }
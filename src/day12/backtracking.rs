@@ -0,0 +1,382 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use super::{PackResult, PackingProblem, PackingSolver, PartialPacking};
+
+// fills the region in reading order: each empty cell is either covered by a placement that
+// starts there or left empty, while the region has spare cells to leave empty (or, when
+// maximising, while the best packing so far can still be beaten). Shapes with the same set
+// of placements are searched together, so identical presents are never permuted
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BacktrackingSolver;
+
+impl BacktrackingSolver {
+    // splits each group's picks between its shapes; a group's placements are in the order of
+    // its first shape, so only the others need their own indices looked up
+    fn assign(problem: &PackingProblem, groups: &[Group], chosen: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let mut chosen_by_shape = vec![Vec::new(); problem.shape_counts.len()];
+
+        for (group_index, group) in groups.iter().enumerate() {
+            let mut placements = chosen
+                .iter()
+                .filter(|&&(group, _)| group == group_index)
+                .map(|&(_, placement)| placement);
+
+            let (&first_shape, other_shapes) = group.shapes.split_first().unwrap();
+            chosen_by_shape[first_shape] = placements.by_ref().take(problem.shape_counts[first_shape]).collect();
+
+            for &shape in other_shapes {
+                let index_by_cells: HashMap<&Vec<usize>, usize> = problem.placements_by_shape[shape]
+                    .iter()
+                    .enumerate()
+                    .map(|(index, cells)| (cells, index))
+                    .collect();
+
+                chosen_by_shape[shape] = placements
+                    .by_ref()
+                    .take(problem.shape_counts[shape])
+                    .map(|placement| index_by_cells[&group.placements[placement]])
+                    .collect();
+            }
+        }
+
+        chosen_by_shape
+    }
+}
+
+impl PackingSolver for BacktrackingSolver {
+    fn pack(&self, problem: &PackingProblem, deadline: Option<Instant>) -> PackResult {
+        let groups = Group::all(problem, &vec![1; problem.shape_counts.len()]);
+        let mut search = Search::new(problem, &groups, deadline);
+
+        let required_cells: usize = groups.iter().map(|group| group.count * group.cell_count()).sum();
+
+        let Some(spare_cells) = (problem.width * problem.height).checked_sub(required_cells) else {
+            return PackResult::Unpackable;
+        };
+
+        if search.run(0, spare_cells) {
+            return PackResult::Packed(Self::assign(problem, &groups, &search.chosen));
+        }
+
+        if search.timed_out {
+            return PackResult::TimedOut;
+        }
+
+        PackResult::Unpackable
+    }
+
+    fn maximize(&self, problem: &PackingProblem, weights: &[usize], deadline: Option<Instant>) -> PartialPacking {
+        let groups = Group::all(problem, weights);
+        let mut search = Search::new(problem, &groups, deadline);
+
+        search.maximize(0, 0);
+
+        PartialPacking {
+            chosen_by_shape: Self::assign(problem, &groups, &search.best),
+            optimal: !search.timed_out,
+        }
+    }
+}
+
+// shapes that can be placed in exactly the same ways, with their counts combined
+struct Group {
+    shapes: Vec<usize>,
+    count: usize,
+    weight: usize,
+    // sorted cell indices (y * width + x) of each of the first shape's placements
+    placements: Vec<Vec<usize>>,
+    // the distinct placements with their first cell moved to 0, to rule out most
+    // comparisons between groups cheaply
+    patterns: Vec<Vec<usize>>,
+}
+
+impl Group {
+    fn new(shape: usize, count: usize, weight: usize, placements: Vec<Vec<usize>>) -> Self {
+        let mut patterns: Vec<Vec<usize>> = Vec::new();
+
+        for cells in placements.iter() {
+            let pattern: Vec<usize> = cells.iter().map(|&cell| cell - cells[0]).collect();
+
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+
+        patterns.sort();
+
+        Self { shapes: vec![shape], count, weight, placements, patterns }
+    }
+
+    fn all(problem: &PackingProblem, weights: &[usize]) -> Vec<Group> {
+        let mut groups: Vec<Group> = Vec::new();
+
+        for (shape, placements) in problem.placements_by_shape.iter().enumerate() {
+            let group = Group::new(shape, problem.shape_counts[shape], weights[shape], placements.clone());
+
+            match groups.iter_mut().find(|other| other.has_same_placements(&group)) {
+                Some(other) => {
+                    other.shapes.push(shape);
+                    other.count += group.count;
+                }
+                None => groups.push(group),
+            }
+        }
+
+        groups
+    }
+
+    fn cell_count(&self) -> usize {
+        self.patterns.first().map_or(0, |cells| cells.len())
+    }
+
+    fn has_same_placements(&self, other: &Group) -> bool {
+        if self.weight != other.weight
+            || self.patterns != other.patterns
+            || self.placements.len() != other.placements.len()
+        {
+            return false;
+        }
+
+        let placements: HashSet<&Vec<usize>> = self.placements.iter().collect();
+        other.placements.iter().all(|cells| placements.contains(cells))
+    }
+}
+
+struct Search<'a> {
+    groups: &'a [Group],
+    deadline: Option<Instant>,
+    steps: usize,
+    timed_out: bool,
+    occupied: Vec<bool>,
+    // (group, placement) pairs keyed by the first cell each placement covers
+    placements_by_first_cell: Vec<Vec<(usize, usize)>>,
+    remaining: Vec<usize>,
+    remaining_total: usize,
+    chosen: Vec<(usize, usize)>,
+    // only used when maximising
+    value: usize,
+    remaining_value: usize,
+    best_value: usize,
+    best: Vec<(usize, usize)>,
+}
+
+impl<'a> Search<'a> {
+    // how many steps to take between looks at the clock
+    const DEADLINE_INTERVAL: usize = 1 << 12;
+
+    fn new(problem: &PackingProblem, groups: &'a [Group], deadline: Option<Instant>) -> Self {
+        let cell_count = problem.width * problem.height;
+        let mut placements_by_first_cell = vec![Vec::new(); cell_count];
+
+        for (group_index, group) in groups.iter().enumerate() {
+            if group.count == 0 {
+                continue;
+            }
+
+            for (placement_index, cells) in group.placements.iter().enumerate() {
+                placements_by_first_cell[cells[0]].push((group_index, placement_index));
+            }
+        }
+
+        let remaining: Vec<usize> = groups.iter().map(|group| group.count).collect();
+
+        Self {
+            groups,
+            deadline,
+            steps: 0,
+            timed_out: false,
+            occupied: vec![false; cell_count],
+            placements_by_first_cell,
+            remaining_total: remaining.iter().sum(),
+            remaining_value: groups.iter().map(|group| group.count * group.weight).sum(),
+            remaining,
+            chosen: Vec::new(),
+            value: 0,
+            best_value: 0,
+            best: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, start: usize, spare_cells: usize) -> bool {
+        if self.remaining_total == 0 {
+            return true;
+        }
+
+        if self.out_of_time() {
+            return false;
+        }
+
+        let Some(cell) = (start..self.occupied.len()).find(|&cell| !self.occupied[cell]) else {
+            return false;
+        };
+
+        for index in 0..self.placements_by_first_cell[cell].len() {
+            let (group, placement) = self.placements_by_first_cell[cell][index];
+
+            if self.remaining[group] == 0 || !self.fits(group, placement) {
+                continue;
+            }
+
+            self.place(group, placement);
+
+            if self.run(cell + 1, spare_cells) {
+                return true;
+            }
+
+            self.remove(group, placement);
+        }
+
+        spare_cells > 0 && self.run(cell + 1, spare_cells - 1)
+    }
+
+    // `occupied_ahead` counts the occupied cells from `start` on
+    fn maximize(&mut self, start: usize, mut occupied_ahead: usize) {
+        if self.value > self.best_value {
+            self.best_value = self.value;
+            self.best = self.chosen.clone();
+        }
+
+        if self.out_of_time() {
+            return;
+        }
+
+        let mut cell = start;
+
+        while cell < self.occupied.len() && self.occupied[cell] {
+            occupied_ahead -= 1;
+            cell += 1;
+        }
+
+        let free_cells = self.occupied.len() - cell - occupied_ahead;
+
+        if self.value + self.value_bound(free_cells) <= self.best_value {
+            return;
+        }
+
+        for index in 0..self.placements_by_first_cell[cell].len() {
+            let (group, placement) = self.placements_by_first_cell[cell][index];
+
+            if self.remaining[group] == 0 || !self.fits(group, placement) {
+                continue;
+            }
+
+            let cell_count = self.groups[group].placements[placement].len();
+
+            self.place(group, placement);
+            self.maximize(cell + 1, occupied_ahead + cell_count - 1);
+            self.remove(group, placement);
+        }
+
+        self.maximize(cell + 1, occupied_ahead);
+    }
+
+    // the most value that `free_cells` more cells could hold
+    fn value_bound(&self, free_cells: usize) -> usize {
+        let by_cells = self.groups
+            .iter()
+            .zip(self.remaining.iter())
+            .filter(|&(group, &remaining)| remaining > 0 && group.cell_count() > 0)
+            .map(|(group, _)| free_cells * group.weight / group.cell_count())
+            .max()
+            .unwrap_or(0);
+
+        by_cells.min(self.remaining_value)
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        self.steps += 1;
+
+        if !self.timed_out && self.steps.is_multiple_of(Self::DEADLINE_INTERVAL) {
+            self.timed_out = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        }
+
+        self.timed_out
+    }
+
+    fn fits(&self, group: usize, placement: usize) -> bool {
+        self.groups[group].placements[placement].iter().all(|&cell| !self.occupied[cell])
+    }
+
+    fn place(&mut self, group: usize, placement: usize) {
+        self.set_occupied(group, placement, true);
+        self.remaining[group] -= 1;
+        self.remaining_total -= 1;
+        self.value += self.groups[group].weight;
+        self.remaining_value -= self.groups[group].weight;
+        self.chosen.push((group, placement));
+    }
+
+    fn remove(&mut self, group: usize, placement: usize) {
+        self.set_occupied(group, placement, false);
+        self.remaining[group] += 1;
+        self.remaining_total += 1;
+        self.value -= self.groups[group].weight;
+        self.remaining_value += self.groups[group].weight;
+        self.chosen.pop();
+    }
+
+    fn set_occupied(&mut self, group: usize, placement: usize, occupied: bool) {
+        for &cell in self.groups[group].placements[placement].iter() {
+            self.occupied[cell] = occupied;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    fn problem(width: usize, height: usize, shape_counts: Vec<usize>, shapes: &[&[(usize, usize)]]) -> PackingProblem {
+        let placements_by_shape = shapes
+            .iter()
+            .map(|cells| {
+                let shape_width = cells.iter().map(|&(x, _)| x).max().unwrap() + 1;
+                let shape_height = cells.iter().map(|&(_, y)| y).max().unwrap() + 1;
+
+                (0..=height - shape_height)
+                    .flat_map(|y| (0..=width - shape_width).map(move |x| (x, y)))
+                    .map(|(x, y)| cells.iter().map(|&(dx, dy)| (y + dy) * width + x + dx).sorted().collect())
+                    .collect()
+            })
+            .collect();
+
+        PackingProblem { width, height, shape_counts, placements_by_shape }
+    }
+
+    #[test]
+    fn packs_identical_shapes_as_one_group() {
+        let domino: &[(usize, usize)] = &[(0, 0), (1, 0)];
+        let problem = problem(4, 1, vec![1, 1], &[domino, domino]);
+
+        let PackResult::Packed(chosen) = BacktrackingSolver.pack(&problem, None) else {
+            panic!("expected a packing");
+        };
+
+        assert_eq!(chosen.iter().map(|picks| picks.len()).collect::<Vec<_>>(), vec![1, 1]);
+        assert_ne!(problem.placements_by_shape[0][chosen[0][0]], problem.placements_by_shape[1][chosen[1][0]]);
+    }
+
+    #[test]
+    fn rejects_regions_that_only_fit_by_area() {
+        let domino: &[(usize, usize)] = &[(0, 0), (1, 0)];
+
+        assert_eq!(BacktrackingSolver.pack(&problem(3, 3, vec![4], &[domino]), None), PackResult::Unpackable);
+        assert!(matches!(BacktrackingSolver.pack(&problem(4, 2, vec![4], &[domino]), None), PackResult::Packed(_)));
+    }
+
+    #[test]
+    fn maximizes_the_weight_of_the_placed_shapes() {
+        let domino: &[(usize, usize)] = &[(0, 0), (1, 0)];
+        let triomino: &[(usize, usize)] = &[(0, 0), (1, 0), (2, 0)];
+        let problem = problem(3, 3, vec![4, 2], &[domino, triomino]);
+
+        let by_count = BacktrackingSolver.maximize(&problem, &[1, 1], None);
+        let by_cells = BacktrackingSolver.maximize(&problem, &[2, 3], None);
+
+        assert!(by_count.optimal && by_cells.optimal);
+        assert_eq!(by_count.chosen_by_shape.iter().map(|picks| picks.len()).sum::<usize>(), 3);
+        assert_eq!(by_cells.chosen_by_shape.iter().map(|picks| picks.len()).collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::Instant;
+use cp_sat::builder::{BoolVar, CpModelBuilder, LinearExpr};
+use cp_sat::proto::{CpSolverResponse, CpSolverStatus, SatParameters};
+use super::{PackResult, PackingProblem, PackingSolver, PartialPacking};
+
+// one boolean per candidate placement, handed to OR-Tools' CP-SAT solver
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CpSatSolver;
+
+impl CpSatSolver {
+    fn sum(vars: &[BoolVar]) -> LinearExpr {
+        vars.iter().fold(0.into(), |acc: LinearExpr, &v| acc + v)
+    }
+
+    // the counts are exact when packing and upper bounds when maximising
+    fn model(problem: &PackingProblem, exact_counts: bool) -> (CpModelBuilder, Vec<Vec<BoolVar>>) {
+        let mut model = CpModelBuilder::default();
+        let placement_vars_by_shape = Self::placement_vars_by_shape(problem, &mut model);
+
+        Self::add_shape_count_constraints(problem, &mut model, &placement_vars_by_shape, exact_counts);
+        Self::add_overlap_constraints(problem, &mut model, &placement_vars_by_shape);
+
+        (model, placement_vars_by_shape)
+    }
+
+    fn parameters(deadline: Option<Instant>) -> SatParameters {
+        SatParameters {
+            max_time_in_seconds: deadline
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()).as_secs_f64()),
+            ..Default::default()
+        }
+    }
+
+    fn chosen_by_shape(placement_vars_by_shape: &[Vec<BoolVar>], response: &CpSolverResponse) -> Vec<Vec<usize>> {
+        placement_vars_by_shape
+            .iter()
+            .map(|vars| {
+                vars.iter()
+                    .enumerate()
+                    .filter(|(_, var)| var.solution_value(response))
+                    .map(|(index, _)| index)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn add_shape_count_constraints(
+        problem: &PackingProblem,
+        model: &mut CpModelBuilder,
+        placement_vars_by_shape: &[Vec<BoolVar>],
+        exact_counts: bool,
+    ) {
+        for (vars, &count) in placement_vars_by_shape.iter().zip(problem.shape_counts.iter()) {
+            let sum = Self::sum(vars);
+
+            if exact_counts {
+                model.add_eq(sum, count as i64);
+            } else {
+                model.add_le(sum, count as i64);
+            }
+        }
+    }
+
+    fn add_overlap_constraints(
+        problem: &PackingProblem,
+        model: &mut CpModelBuilder,
+        placement_vars_by_shape: &[Vec<BoolVar>],
+    ) {
+        let placement_vars_by_cell = Self::placement_vars_by_cell(problem, placement_vars_by_shape);
+
+        for placement_vars in placement_vars_by_cell.iter().filter(|vars| !vars.is_empty()) {
+            model.add_le(Self::sum(placement_vars), 1);
+        }
+    }
+
+    fn placement_vars_by_shape(problem: &PackingProblem, model: &mut CpModelBuilder) -> Vec<Vec<BoolVar>> {
+        problem.placements_by_shape
+            .iter()
+            .map(|placements| placements.iter().map(|_| model.new_bool_var()).collect())
+            .collect()
+    }
+
+    fn placement_vars_by_cell(problem: &PackingProblem, placement_vars_by_shape: &[Vec<BoolVar>]) -> Vec<Vec<BoolVar>> {
+        problem.placements_by_shape
+            .iter()
+            .zip(placement_vars_by_shape.iter())
+            .flat_map(|(placements, vars)| placements.iter().zip(vars.iter()))
+            .fold(vec![Vec::new(); problem.width * problem.height], |mut result, (cells, &var)| {
+                for &cell in cells {
+                    result[cell].push(var);
+                }
+
+                result
+            })
+    }
+}
+
+impl PackingSolver for CpSatSolver {
+    fn pack(&self, problem: &PackingProblem, deadline: Option<Instant>) -> PackResult {
+        let (model, placement_vars_by_shape) = Self::model(problem, true);
+        let response = model.solve_with_parameters(&Self::parameters(deadline));
+
+        match response.status() {
+            CpSolverStatus::Optimal | CpSolverStatus::Feasible => {
+                PackResult::Packed(Self::chosen_by_shape(&placement_vars_by_shape, &response))
+            }
+            CpSolverStatus::Unknown => PackResult::TimedOut,
+            _ => PackResult::Unpackable,
+        }
+    }
+
+    fn maximize(&self, problem: &PackingProblem, weights: &[usize], deadline: Option<Instant>) -> PartialPacking {
+        let (mut model, placement_vars_by_shape) = Self::model(problem, false);
+
+        let objective: LinearExpr = placement_vars_by_shape
+            .iter()
+            .zip(weights.iter())
+            .flat_map(|(vars, &weight)| vars.iter().map(move |&var| (weight as i64, var)))
+            .collect();
+
+        model.maximize(objective);
+
+        let response = model.solve_with_parameters(&Self::parameters(deadline));
+
+        match response.status() {
+            CpSolverStatus::Optimal => PartialPacking {
+                chosen_by_shape: Self::chosen_by_shape(&placement_vars_by_shape, &response),
+                optimal: true,
+            },
+            CpSolverStatus::Feasible => PartialPacking {
+                chosen_by_shape: Self::chosen_by_shape(&placement_vars_by_shape, &response),
+                optimal: false,
+            },
+            // out of time before finding anything; placing nothing is always possible
+            _ => PartialPacking {
+                chosen_by_shape: vec![Vec::new(); problem.shape_counts.len()],
+                optimal: false,
+            },
+        }
+    }
+}
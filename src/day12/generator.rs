@@ -0,0 +1,246 @@
+use std::ops::RangeInclusive;
+
+use super::{PresentPlacement, Region, RegionOutcome, RegionProblem, Shape, ShapeLibrary, TreeFarm, Verdict};
+use crate::{Error, Result};
+
+// builds puzzles whose answers are known up front: packable regions are filled by placing
+// presents at random, unpackable ones are certified by the capacity or colouring pre-check.
+// The same seed and shapes always give the same puzzle
+pub struct PuzzleGenerator {
+    shapes: Vec<Shape>,
+    library: ShapeLibrary,
+    random: SplitMix64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedPuzzle {
+    pub tree_farm: TreeFarm,
+    // one per region
+    pub truths: Vec<GroundTruth>,
+}
+
+impl GeneratedPuzzle {
+    pub fn packable_regions(&self) -> usize {
+        self.truths.iter().filter(|truth| truth.is_packable()).count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroundTruth {
+    // a packing that fits every present
+    Packable(Vec<PresentPlacement>),
+    // the pre-check that proves it can't be packed: `NoCapacity` or `ColoringMismatch`
+    Unpackable(RegionOutcome),
+}
+
+impl GroundTruth {
+    pub fn is_packable(&self) -> bool {
+        matches!(self, GroundTruth::Packable(_))
+    }
+}
+
+struct GeneratedRegion {
+    region: Region,
+    shape_counts: Vec<usize>,
+    truth: GroundTruth,
+}
+
+impl PuzzleGenerator {
+    // random presents tried per region cell when filling a packable region
+    const ATTEMPTS_PER_CELL: usize = 4;
+    // regions tried before giving up on a colouring mismatch
+    const COLORING_ATTEMPTS: usize = 32;
+
+    pub fn new(shapes: Vec<Shape>, seed: u64) -> Result<Self> {
+        if shapes.is_empty() {
+            return Err(Error::validation("The generator needs at least one shape"));
+        }
+
+        let library = ShapeLibrary::new(&shapes)?;
+
+        Ok(Self { shapes, library, random: SplitMix64(seed) })
+    }
+
+    // regions are split between packable, over capacity and (when the shapes allow it)
+    // colouring mismatches; sides are drawn from `sizes`
+    pub fn puzzle(&mut self, region_count: usize, sizes: RangeInclusive<usize>) -> Result<GeneratedPuzzle> {
+        if sizes.is_empty() || *sizes.start() == 0 {
+            return Err(Error::validation(format!("Invalid region sizes: {:?}", sizes)));
+        }
+
+        let generated: Vec<GeneratedRegion> = (0..region_count)
+            .map(|_| {
+                let width = self.random.in_range(&sizes);
+                let height = self.random.in_range(&sizes);
+
+                match self.random.below(3) {
+                    0 => self.over_capacity(width, height),
+                    1 => self.coloring_mismatch(&sizes).unwrap_or_else(|| self.packable(width, height)),
+                    _ => self.packable(width, height),
+                }
+            })
+            .collect();
+
+        let mut regions = Vec::new();
+        let mut shape_counts_by_region = Vec::new();
+        let mut truths = Vec::new();
+
+        for region in generated {
+            regions.push(region.region);
+            shape_counts_by_region.push(region.shape_counts);
+            truths.push(region.truth);
+        }
+
+        let tree_farm = TreeFarm::new(self.shapes.clone(), regions, shape_counts_by_region)?;
+
+        Ok(GeneratedPuzzle { tree_farm, truths })
+    }
+
+    // drops presents in random orientations and positions, keeping those that don't overlap
+    fn packable(&mut self, width: usize, height: usize) -> GeneratedRegion {
+        let mut occupied = vec![vec![false; width]; height];
+        let mut shape_counts = vec![0; self.shapes.len()];
+        let mut placements = Vec::new();
+
+        for _ in 0..width * height * Self::ATTEMPTS_PER_CELL {
+            let shape = self.random.below(self.shapes.len());
+            let orientations = self.library.shape(shape).unwrap().orientations();
+            let orientation_index = self.random.below(orientations.len());
+            let orientation = &orientations[orientation_index];
+
+            if orientation.width() > width || orientation.height() > height {
+                continue;
+            }
+
+            let x = self.random.below(width - orientation.width() + 1);
+            let y = self.random.below(height - orientation.height() + 1);
+
+            let cells: Vec<(usize, usize)> = orientation
+                .covered_coordinates()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .collect();
+
+            if cells.iter().any(|&(x, y)| occupied[y][x]) {
+                continue;
+            }
+
+            for &(x, y) in cells.iter() {
+                occupied[y][x] = true;
+            }
+
+            shape_counts[shape] += 1;
+            placements.push(PresentPlacement { shape, orientation: orientation_index, x, y });
+        }
+
+        GeneratedRegion { region: Region::new(width, height), shape_counts, truth: GroundTruth::Packable(placements) }
+    }
+
+    // keeps adding presents until they cover more cells than the region has
+    fn over_capacity(&mut self, width: usize, height: usize) -> GeneratedRegion {
+        let region = Region::new(width, height);
+        let mut shape_counts = vec![0; self.shapes.len()];
+        let mut required = 0;
+
+        while required <= region.cell_count() {
+            let shape = self.random.below(self.shapes.len());
+            shape_counts[shape] += 1;
+            required += self.library.shape(shape).unwrap().cell_count();
+        }
+
+        GeneratedRegion { region, shape_counts, truth: GroundTruth::Unpackable(RegionOutcome::NoCapacity) }
+    }
+
+    // adds presents while they fit by area, until the colouring pre-check rules the region
+    // out; `None` if that never happens, e.g. when every shape covers both colours equally
+    fn coloring_mismatch(&mut self, sizes: &RangeInclusive<usize>) -> Option<GeneratedRegion> {
+        for _ in 0..Self::COLORING_ATTEMPTS {
+            let region = Region::new(self.random.in_range(sizes), self.random.in_range(sizes));
+            let mut shape_counts = vec![0; self.shapes.len()];
+            let mut required = 0;
+
+            loop {
+                let shape = self.random.below(self.shapes.len());
+                required += self.library.shape(shape).unwrap().cell_count();
+
+                if required > region.cell_count() {
+                    break;
+                }
+
+                shape_counts[shape] += 1;
+
+                let problem = RegionProblem::new(&self.library, &region, &shape_counts);
+
+                if let Verdict::DoesNotFit = problem.coloring_check() {
+                    let truth = GroundTruth::Unpackable(RegionOutcome::ColoringMismatch);
+                    return Some(GeneratedRegion { region, shape_counts, truth });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// tiny, and gives the same numbers for a seed on every platform
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // slightly biased towards small numbers, which doesn't matter here
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn in_range(&mut self, range: &RangeInclusive<usize>) -> usize {
+        range.start() + self.below(range.end() - range.start() + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day12::tests::shape;
+    use crate::day12::{BacktrackingSolver, SolveOptions};
+
+    fn generator(seed: u64) -> PuzzleGenerator {
+        let shapes = ["###\n#..\n###", "###\n.#.", "##.\n.##"].into_iter().map(shape).collect();
+
+        PuzzleGenerator::new(shapes, seed).unwrap()
+    }
+
+    #[test]
+    fn generates_the_same_puzzle_for_a_seed() {
+        let first = generator(7).puzzle(5, 3..=8).unwrap();
+
+        assert_eq!(generator(7).puzzle(5, 3..=8).unwrap(), first);
+        assert_ne!(generator(8).puzzle(5, 3..=8).unwrap(), first);
+    }
+
+    #[test]
+    fn solvers_agree_with_the_ground_truth() {
+        let puzzle = generator(2025).puzzle(12, 3..=7).unwrap();
+        let layouts = puzzle.tree_farm
+            .region_layouts_with(&BacktrackingSolver, &SolveOptions::default(), &mut |_: &_| {})
+            .unwrap();
+
+        for (layout, truth) in layouts.iter().zip(puzzle.truths.iter()) {
+            match truth {
+                GroundTruth::Packable(placements) => {
+                    assert!(puzzle.tree_farm.packing_of(layout.region, placements).is_ok());
+                    assert!(layout.is_packable(), "region {} should be packable", layout.region);
+                }
+                GroundTruth::Unpackable(_) => assert!(!layout.is_packable()),
+            }
+        }
+
+        assert!(puzzle.truths.contains(&GroundTruth::Unpackable(RegionOutcome::ColoringMismatch)));
+    }
+}
@@ -0,0 +1,182 @@
+use super::{Region, Shape, TreeFarm};
+use crate::{split_with_columns, Error, Result};
+
+// puzzle    = { shape | blank } { region | blank }
+// shape     = header grid { grid } ( blank { blank } | end )
+// header    = index ":"
+// grid      = ( "#" | "." ) { "#" | "." }
+// region    = width "x" height ":" { " " count }
+//
+// shape indices may skip numbers but have to increase, so the shapes (and each region's
+// counts) are in the order the input lists them
+pub(super) struct PuzzleParser<'a> {
+    lines: &'a [String],
+    index: usize,
+    // with their header index
+    shapes: Vec<(usize, Shape)>,
+    regions: Vec<Region>,
+    shape_counts_by_region: Vec<Vec<usize>>,
+}
+
+enum Line<'a> {
+    Blank,
+    Header(&'a str),
+    Region(&'a str),
+    Other,
+}
+
+impl<'a> PuzzleParser<'a> {
+    pub(super) fn new(lines: &'a [String]) -> Self {
+        Self { lines, index: 0, shapes: Vec::new(), regions: Vec::new(), shape_counts_by_region: Vec::new() }
+    }
+
+    pub(super) fn parse(mut self) -> Result<TreeFarm> {
+        while let Some(line) = self.peek() {
+            match line {
+                Line::Blank => self.index += 1,
+                Line::Header(_) if !self.regions.is_empty() => {
+                    return Err(self.error(1, "Shapes have to come before the regions"));
+                }
+                Line::Header(index) => self.shape(index)?,
+                Line::Region(line) => self.region(line)?,
+                Line::Other => return Err(self.error(1, format!("Unexpected line: {}", self.text()))),
+            }
+        }
+
+        let (shape_indices, shapes) = self.shapes.into_iter().unzip();
+
+        Ok(TreeFarm::new(shapes, self.regions, self.shape_counts_by_region)?.with_shape_indices(shape_indices))
+    }
+
+    fn peek(&self) -> Option<Line<'a>> {
+        let line = self.lines.get(self.index)?.trim();
+
+        if line.is_empty() {
+            return Some(Line::Blank);
+        }
+
+        if let Some(index) = line.strip_suffix(':').filter(|index| Self::is_number(index)) {
+            return Some(Line::Header(index));
+        }
+
+        let dimensions = line.split_once(':').map(|(dimensions, _)| dimensions);
+
+        if dimensions.and_then(|dimensions| dimensions.split_once('x')).is_some_and(|(width, _)| Self::is_number(width)) {
+            return Some(Line::Region(line));
+        }
+
+        Some(Line::Other)
+    }
+
+    fn shape(&mut self, index: &str) -> Result<()> {
+        let index = index.parse::<usize>().map_err(|e| self.error(1, format!("Invalid shape index: {}", e)))?;
+
+        match self.shapes.last() {
+            Some(&(previous, _)) if previous == index => {
+                return Err(self.error(1, format!("Shape {} is defined twice", index)));
+            }
+            Some(&(previous, _)) if previous > index => {
+                return Err(self.error(1, format!("Shape {} has to come before shape {}", index, previous)));
+            }
+            _ => {}
+        }
+
+        self.index += 1;
+
+        let start = self.index;
+
+        while let Some(Line::Other) = self.peek() {
+            self.index += 1;
+        }
+
+        if self.index == start {
+            return Err(self.error(1, format!("Shape {} needs at least one grid line", index)));
+        }
+
+        let shape = Shape::try_from(&self.lines[start..self.index]).map_err(|e| e.offset(start, 0))?;
+
+        if let Some(Line::Header(_) | Line::Region(_)) = self.peek() {
+            return Err(self.error(1, format!("Missing blank line after shape {}", index)));
+        }
+
+        self.shapes.push((index, shape));
+        Ok(())
+    }
+
+    // e.g. "12x5: 1 0 1 0 2 2"
+    fn region(&mut self, line: &str) -> Result<()> {
+        let (dimensions, counts) = line.split_once(':').unwrap();
+        let region = Region::try_from(dimensions).map_err(|e| e.offset(self.index, 0))?;
+
+        // the counts start after the colon
+        let counts_column = dimensions.len() + 1;
+
+        let shape_counts: Vec<usize> = split_with_columns(counts, ' ')
+            .filter(|(_, s)| !s.is_empty())
+            .map(|(column, s)| {
+                s.parse::<usize>().map_err(|e| self.error(counts_column + column + 1, format!("Invalid count: {}", e)))
+            })
+            .collect::<Result<_>>()?;
+
+        if shape_counts.len() != self.shapes.len() {
+            return Err(self.error(
+                counts_column + 2,
+                format!("Expected {} shape counts, got {}", self.shapes.len(), shape_counts.len()),
+            ));
+        }
+
+        self.regions.push(region);
+        self.shape_counts_by_region.push(shape_counts);
+        self.index += 1;
+
+        Ok(())
+    }
+
+    fn is_number(s: &str) -> bool {
+        !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+    }
+
+    fn text(&self) -> &str {
+        self.lines[self.index].trim()
+    }
+
+    // at the current line; columns count from its first non-blank character
+    fn error(&self, column: usize, message: impl Into<String>) -> Error {
+        Error::parse(column, message).offset(self.index, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day12::tests::parse;
+
+    fn error_position(text: &str) -> (usize, usize, String) {
+        match parse(text) {
+            Err(Error::Parse { line, column, message, .. }) => (line, column, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn keeps_gaps_in_shape_indices() {
+        let text = "2:\n###\n\n5:\n#.\n##\n\n4x4: 1 2\n";
+        let tree_farm = parse(text).unwrap();
+
+        assert_eq!(tree_farm.shape_indices(), &[2, 5]);
+        assert_eq!(tree_farm.to_string(), text);
+    }
+
+    #[test]
+    fn rejects_malformed_puzzles() {
+        assert_eq!(error_position("0:\n##\n\nhello\n4x4: 1\n"), (4, 1, "Unexpected line: hello".to_string()));
+        assert_eq!(error_position("0:\n##\n1:\n#\n"), (3, 1, "Missing blank line after shape 0".to_string()));
+        assert_eq!(error_position("0:\n##\n4x4: 1\n"), (3, 1, "Missing blank line after shape 0".to_string()));
+        assert_eq!(error_position("0:\n#a\n\n4x4: 1\n").0, 2);
+        assert_eq!(error_position("0:\n##\n\n4x4: 1 x\n"), (4, 8, "Invalid count: invalid digit found in string".to_string()));
+        assert_eq!(error_position("0:\n##\n\n4x4: 1 2\n"), (4, 6, "Expected 1 shape counts, got 2".to_string()));
+        assert_eq!(error_position("0:\n##\n\n4x4: 1\n\n1:\n#\n"), (6, 1, "Shapes have to come before the regions".to_string()));
+        assert_eq!(error_position("0:\n##\n\n0:\n#\n\n"), (4, 1, "Shape 0 is defined twice".to_string()));
+        assert_eq!(error_position("1:\n##\n\n0:\n#\n\n"), (4, 1, "Shape 0 has to come before shape 1".to_string()));
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::iter;
+
+use itertools::Itertools;
+
+use super::Shape;
+use crate::{Error, Result};
+
+// every distinct shape once, with its orientations worked out up front; input shapes that
+// are rotations or reflections of each other share an entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeLibrary {
+    entries: Vec<InternedShape>,
+    // the entry each input shape was interned as
+    entry_by_shape: Vec<usize>,
+}
+
+impl ShapeLibrary {
+    pub(super) fn new(shapes: &[Shape]) -> Result<Self> {
+        let mut entries = Vec::new();
+        let mut entry_by_canonical = HashMap::new();
+        let mut entry_by_shape = Vec::with_capacity(shapes.len());
+
+        for shape in shapes {
+            let shape = InternedShape::new(Orientation::new(shape)?);
+
+            // the smallest orientation is the same whichever one a shape was drawn in
+            let canonical = shape.orientations.iter().min().unwrap().clone();
+
+            let entry = *entry_by_canonical.entry(canonical).or_insert_with(|| {
+                entries.push(shape);
+                entries.len() - 1
+            });
+
+            entry_by_shape.push(entry);
+        }
+
+        Ok(Self { entries, entry_by_shape })
+    }
+
+    // how many shapes the input lists, duplicates included
+    pub fn shape_count(&self) -> usize {
+        self.entry_by_shape.len()
+    }
+
+    pub fn distinct_shape_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    // by input index; duplicates get the entry of the first shape like them
+    pub fn shape(&self, index: usize) -> Option<&InternedShape> {
+        self.entry_by_shape.get(index).map(|&entry| &self.entries[entry])
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = &InternedShape> {
+        self.entry_by_shape.iter().map(|&entry| &self.entries[entry])
+    }
+
+    // input indices of the shapes that are the same up to rotation and reflection
+    pub fn duplicates(&self) -> Vec<Vec<usize>> {
+        self.entry_by_shape
+            .iter()
+            .enumerate()
+            .into_group_map_by(|&(_, &entry)| entry)
+            .into_iter()
+            .sorted_by_key(|&(entry, _)| entry)
+            .map(|(_, shapes)| shapes.into_iter().map(|(index, _)| index).collect::<Vec<_>>())
+            .filter(|shapes| shapes.len() > 1)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternedShape {
+    // its four rotations, then those of its mirror image, skipping any that repeat
+    orientations: Vec<Orientation>,
+    symmetry: SymmetryGroup,
+}
+
+impl InternedShape {
+    fn new(shape: Orientation) -> Self {
+        let orientations = [shape.clone(), shape.flip()]
+            .into_iter()
+            .flat_map(|orientation| iter::successors(Some(orientation), |o| Some(o.rotate())).take(4))
+            .unique()
+            .collect();
+
+        Self { orientations, symmetry: SymmetryGroup::of(&shape) }
+    }
+
+    pub fn orientations(&self) -> &[Orientation] {
+        &self.orientations
+    }
+
+    pub fn symmetry(&self) -> SymmetryGroup {
+        self.symmetry
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.orientations[0].cell_count()
+    }
+
+    // how many more cells of one checkerboard colour the shape covers than of the other;
+    // the same in every orientation
+    pub(super) fn color_imbalance(&self) -> usize {
+        let cells = self.orientations[0].covered_coordinates();
+        let black = cells.filter(|&(x, y)| (x + y) % 2 == 0).count();
+
+        black.abs_diff(self.cell_count() - black)
+    }
+}
+
+// the rotations and reflections that leave a shape unchanged: Cn for n-fold rotational
+// symmetry alone, Dn for the same with n mirror axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymmetryGroup {
+    C1,
+    C2,
+    C4,
+    D1,
+    D2,
+    D4,
+}
+
+impl SymmetryGroup {
+    fn of(shape: &Orientation) -> Self {
+        let turns = iter::successors(Some(shape.clone()), |o| Some(o.rotate())).take(4);
+        let rotations = turns.filter(|turn| turn == shape).count();
+
+        let reflective = iter::successors(Some(shape.flip()), |o| Some(o.rotate()))
+            .take(4)
+            .any(|turn| turn == *shape);
+
+        match (rotations, reflective) {
+            (1, false) => SymmetryGroup::C1,
+            (2, false) => SymmetryGroup::C2,
+            (_, false) => SymmetryGroup::C4,
+            (1, true) => SymmetryGroup::D1,
+            (2, true) => SymmetryGroup::D2,
+            (_, true) => SymmetryGroup::D4,
+        }
+    }
+
+    // how many of the eight rotations and reflections leave the shape as it is
+    pub fn order(&self) -> usize {
+        match self {
+            SymmetryGroup::C1 => 1,
+            SymmetryGroup::C2 | SymmetryGroup::D1 => 2,
+            SymmetryGroup::C4 | SymmetryGroup::D2 => 4,
+            SymmetryGroup::D4 => 8,
+        }
+    }
+
+    pub fn orientation_count(&self) -> usize {
+        8 / self.order()
+    }
+}
+
+impl fmt::Display for SymmetryGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// a shape as it's placed: bit x of `rows[y]` is set when (x, y) is covered
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Orientation {
+    width: usize,
+    height: usize,
+    rows: Vec<u64>,
+}
+
+impl Orientation {
+    // rotations swap width and height, so both have to fit in a row
+    const MAX_SIZE: usize = u64::BITS as usize;
+
+    fn new(shape: &Shape) -> Result<Self> {
+        if shape.width() > Self::MAX_SIZE || shape.height() > Self::MAX_SIZE {
+            return Err(Error::validation(format!(
+                "Shapes can be at most {0}x{0}, got {1}x{2}",
+                Self::MAX_SIZE,
+                shape.width(),
+                shape.height()
+            )));
+        }
+
+        let mut rows = vec![0; shape.height()];
+
+        for &(x, y) in shape.covered_coordinates() {
+            rows[y] |= 1 << x;
+        }
+
+        Ok(Self { width: shape.width(), height: shape.height(), rows })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn rows(&self) -> &[u64] {
+        &self.rows
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.rows.iter().map(|row| row.count_ones() as usize).sum()
+    }
+
+    // row by row, left to right
+    pub fn covered_coordinates(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rows.iter().enumerate().flat_map(move |(y, &row)| {
+            (0..self.width).filter(move |&x| row >> x & 1 == 1).map(move |x| (x, y))
+        })
+    }
+
+    // read off the row masks: the cells covered with the top left corner at (x, y) of a region
+    // `width` cells wide, as sorted indices y * width + x
+    pub fn cell_indices(&self, x: usize, y: usize, width: usize) -> impl Iterator<Item = usize> + '_ {
+        self.rows.iter().enumerate().flat_map(move |(dy, &row)| {
+            let start = (y + dy) * width + x;
+
+            // each step clears the lowest set bit
+            iter::successors(Some(row).filter(|&bits| bits != 0), |&bits| Some(bits & (bits - 1)).filter(|&bits| bits != 0))
+                .map(move |bits| start + bits.trailing_zeros() as usize)
+        })
+    }
+
+    // a quarter turn: (x, y) moves to (y, width - 1 - x)
+    fn rotate(&self) -> Self {
+        let mut rows = vec![0; self.width];
+
+        for (x, y) in self.covered_coordinates() {
+            rows[self.width - 1 - x] |= 1 << y;
+        }
+
+        Self { width: self.height, height: self.width, rows }
+    }
+
+    // mirrored left to right
+    fn flip(&self) -> Self {
+        let rows = self.rows
+            .iter()
+            .map(|row| row.reverse_bits() >> (Self::MAX_SIZE - self.width))
+            .collect();
+
+        Self { width: self.width, height: self.height, rows }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day12::tests::shape;
+
+    fn library(shapes: &[&str]) -> ShapeLibrary {
+        let shapes: Vec<Shape> = shapes.iter().map(|text| shape(text)).collect();
+        ShapeLibrary::new(&shapes).unwrap()
+    }
+
+    #[test]
+    fn classifies_symmetry_groups() {
+        let library = library(&["##.\n.##\n.#.", "##.\n.##", ".#..\n.###\n###.\n..#.", "###\n.#.", "###", "##\n##"]);
+
+        let symmetries: Vec<(SymmetryGroup, usize)> = library
+            .shapes()
+            .map(|shape| (shape.symmetry(), shape.orientations().len()))
+            .collect();
+
+        assert_eq!(
+            symmetries,
+            vec![
+                (SymmetryGroup::C1, 8),
+                (SymmetryGroup::C2, 4),
+                (SymmetryGroup::C4, 2),
+                (SymmetryGroup::D1, 4),
+                (SymmetryGroup::D2, 2),
+                (SymmetryGroup::D4, 1),
+            ]
+        );
+
+        assert!(symmetries.iter().all(|(symmetry, count)| symmetry.orientation_count() == *count));
+    }
+
+    #[test]
+    fn interns_shapes_that_match_up_to_rotation_and_reflection() {
+        let library = library(&["###\n.#.", "#.\n##\n#.", "##", ".#.\n###", "#\n#"]);
+
+        assert_eq!(library.shape_count(), 5);
+        assert_eq!(library.distinct_shape_count(), 2);
+        assert_eq!(library.duplicates(), vec![vec![0, 1, 3], vec![2, 4]]);
+        assert_eq!(library.shape(3), library.shape(0));
+        assert_eq!(library.shape(0).unwrap().orientations()[1].rows(), &[0b01, 0b11, 0b01]);
+
+        let cells: Vec<usize> = library.shape(0).unwrap().orientations()[0].cell_indices(1, 1, 5).collect();
+        assert_eq!(cells, vec![6, 7, 8, 12]);
+    }
+}
@@ -0,0 +1,140 @@
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::{Error, Result};
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+const INPUT_DIR_VARIABLE: &str = "AOC_INPUT_DIR";
+const STDIN_ARGUMENT: &str = "-";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    // an explicit path (or "-" for stdin) wins, then $AOC_INPUT_DIR, then the bundled inputs
+    pub fn resolve(day: u8, argument: Option<&str>) -> Self {
+        Self::resolve_in(day, argument, env::var_os(INPUT_DIR_VARIABLE))
+    }
+
+    fn resolve_in(day: u8, argument: Option<&str>, input_dir: Option<OsString>) -> Self {
+        match argument {
+            Some(STDIN_ARGUMENT) => Source::Stdin,
+            Some(path) => Source::Path(PathBuf::from(path)),
+            None => {
+                let directory = input_dir
+                    .filter(|directory| !directory.is_empty())
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| PathBuf::from(MANIFEST_DIR).join("input"));
+
+                Source::Path(directory.join(format!("day{:02}.txt", day)))
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            Source::Path(path) => path,
+            Source::Stdin => Path::new("<stdin>"),
+        }
+    }
+
+    fn read_to_string(&self) -> io::Result<String> {
+        match self {
+            Source::Path(path) => fs::read_to_string(path),
+            Source::Stdin => {
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content)?;
+                Ok(content)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path().display())
+    }
+}
+
+pub struct Input {
+    source: Source,
+    lines: Vec<String>,
+}
+
+impl Input {
+    pub fn for_day(day: u8) -> Result<Self> {
+        Self::read(Source::resolve(day, None))
+    }
+
+    // the first command-line argument, if present, is the input path (or "-")
+    pub fn from_args(day: u8) -> Result<Self> {
+        Self::read(Source::resolve(day, env::args().nth(1).as_deref()))
+    }
+
+    pub fn read(source: Source) -> Result<Self> {
+        let content = source
+            .read_to_string()
+            .map_err(|e| Error::Io { path: Some(source.path().to_path_buf()), source: e })?;
+        let lines = content.lines().map(String::from).collect();
+
+        Ok(Self { source, lines })
+    }
+
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    // runs a parser over the lines, pointing any parse error back into the file
+    pub fn parse<T>(&self, parser: impl FnOnce(&Vec<String>) -> Result<T>) -> Result<T> {
+        parser(&self.lines).map_err(|e| e.with_source(self.source.path(), &self.lines))
+    }
+}
+
+pub fn input_for_day(day: u8) -> Result<Vec<String>> {
+    Ok(Input::for_day(day)?.lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_sources_in_order() {
+        let input_dir = Some(OsString::from("/tmp/inputs"));
+
+        assert_eq!(
+            Source::resolve_in(7, Some("mine.txt"), input_dir.clone()),
+            Source::Path(PathBuf::from("mine.txt"))
+        );
+        assert_eq!(Source::resolve_in(7, Some("-"), input_dir.clone()), Source::Stdin);
+        assert_eq!(
+            Source::resolve_in(7, None, input_dir),
+            Source::Path(PathBuf::from("/tmp/inputs/day07.txt"))
+        );
+        assert_eq!(
+            Source::resolve_in(7, None, Some(OsString::new())),
+            Source::Path(PathBuf::from(MANIFEST_DIR).join("input").join("day07.txt"))
+        );
+        assert_eq!(
+            Source::resolve_in(12, None, None),
+            Source::Path(PathBuf::from(MANIFEST_DIR).join("input").join("day12.txt"))
+        );
+    }
+
+    #[test]
+    fn names_the_file_it_failed_to_read() {
+        let error = Input::read(Source::Path(PathBuf::from("/nonexistent/day04.txt"))).err().unwrap();
+        assert!(error.to_string().starts_with("I/O error reading /nonexistent/day04.txt: "));
+    }
+}
@@ -1,9 +1,8 @@
-use std::fs;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::io;
 
-const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
-
+pub mod bench;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -16,13 +15,173 @@ pub mod day09;
 pub mod day10;
 pub mod day11;
 pub mod day12;
+pub mod input;
+pub mod runner;
+pub mod solution;
+
+pub use input::{input_for_day, Input, Source};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub enum Error {
+    // `path` is the file that couldn't be read, when it's known
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    // line and column are 1-based and relative to the text handed to the parser
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+        source_line: Option<Box<SourceLine>>,
+    },
+    Validation(String),
+    Solver(String),
+}
+
+// the input line a parse error points into, attached once the input file is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLine {
+    pub path: PathBuf,
+    pub text: String,
+}
+
+impl Error {
+    pub fn parse(column: usize, message: impl Into<String>) -> Self {
+        Error::Parse { line: 1, column, message: message.into(), source_line: None }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Error::Validation(message.into())
+    }
+
+    pub fn solver(message: impl Into<String>) -> Self {
+        Error::Solver(message.into())
+    }
+
+    // moves a parse position from a sub-slice of the input into the enclosing input
+    pub fn offset(self, lines: usize, columns: usize) -> Self {
+        match self {
+            Error::Parse { line, column, message, source_line } => Error::Parse {
+                line: line + lines,
+                column: column + columns,
+                message,
+                source_line,
+            },
+            other => other,
+        }
+    }
+
+    // positions must already be relative to the whole file
+    pub fn with_source(self, path: &Path, lines: &[String]) -> Self {
+        match self {
+            Error::Parse { line, column, message, .. } => Error::Parse {
+                line,
+                column,
+                message,
+                source_line: line.checked_sub(1).and_then(|index| lines.get(index)).map(|text| {
+                    Box::new(SourceLine { path: path.to_path_buf(), text: text.clone() })
+                }),
+            },
+            other => other,
+        }
+    }
+
+    fn fmt_diagnostic(
+        f: &mut fmt::Formatter<'_>,
+        line: usize,
+        column: usize,
+        message: &str,
+        source_line: &SourceLine,
+    ) -> fmt::Result {
+        let gutter = " ".repeat(line.to_string().len());
+
+        writeln!(f, "error: {}", message)?;
+        writeln!(f, "{}--> {}:{}:{}", gutter, source_line.path.display(), line, column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line, source_line.text)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(column.saturating_sub(1)))
+    }
+}
+
+// splits on `separator`, pairing each piece with its 0-based column, so an error from parsing
+// a piece can be moved into place with `Error::offset(0, column)`
+pub fn split_with_columns(text: &str, separator: char) -> impl Iterator<Item = (usize, &str)> {
+    text.split(separator).scan(0, |column, piece| {
+        let start = *column;
+        *column += piece.len() + 1;
+        Some((start, piece))
+    })
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path: Some(path), source } => write!(f, "I/O error reading {}: {}", path.display(), source),
+            Error::Io { path: None, source } => write!(f, "I/O error: {}", source),
+            Error::Parse { line, column, message, source_line: Some(source_line) } => {
+                Self::fmt_diagnostic(f, *line, *column, message, source_line)
+            }
+            Error::Parse { line, column, message, source_line: None } => {
+                write!(f, "parse error at line {}, column {}: {}", line, column, message)
+            }
+            Error::Validation(message) => write!(f, "validation error: {}", message),
+            Error::Solver(message) => write!(f, "solver error: {}", message),
+        }
+    }
+}
+
+// `main` functions returning this error print it with `Debug`, so show the diagnostic
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io { path: None, source: e }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_parse_errors_against_the_source_line() {
+        let lines = vec!["4x4: 1 0".to_string(), "4x4: 1 z".to_string()];
+
+        let error = Error::parse(3, "Invalid count")
+            .offset(0, 5)
+            .offset(1, 0)
+            .with_source(Path::new("input/day12.txt"), &lines);
+
+        let expected = [
+            "error: Invalid count",
+            " --> input/day12.txt:2:8",
+            "  |",
+            "2 | 4x4: 1 z",
+            "  |        ^",
+        ]
+        .join("\n");
 
-pub fn input_for_day(day: u8) -> io::Result<Vec<String>> {
-    let directory = PathBuf::from(MANIFEST_DIR).join("input");
-    let filename = format!("day{:02}.txt", day);
-    let path = directory.join(filename);
-    let content = fs::read_to_string(&path)?;
-    let lines = content.lines().map(String::from).collect();
+        assert_eq!(error.to_string(), expected);
+    }
 
-    Ok(lines)
+    #[test]
+    fn splits_with_columns() {
+        let pieces: Vec<(usize, &str)> = split_with_columns("12,,345,6", ',').collect();
+        assert_eq!(pieces, vec![(0, "12"), (3, ""), (4, "345"), (8, "6")]);
+    }
 }
@@ -0,0 +1,449 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use crate::{Error, Input, Result, Source};
+use crate::solution::{Answer, Solution};
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12};
+
+// parts that aren't requested are left as `Ok(None)`
+type SolveFn = fn(&[String], &[Part]) -> Result<Answers>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+pub struct Day {
+    number: u8,
+    solve: SolveFn,
+}
+
+impl Day {
+    const fn new(number: u8, solve: SolveFn) -> Self {
+        Self { number, solve }
+    }
+
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    pub fn run(&self, argument: Option<&str>) -> Result<Answers> {
+        let input = Input::read(Source::resolve(self.number, argument))?;
+        input.parse(|lines| self.solve(lines, &[Part::One, Part::Two]))
+    }
+
+    // solves a single part, for inputs that only make sense for one of them
+    pub fn run_part(&self, part: Part, argument: Option<&str>) -> Result<Option<String>> {
+        let input = Input::read(Source::resolve(self.number, argument))?;
+        let answers = input.parse(|lines| self.solve(lines, &[part]))?;
+
+        match part {
+            Part::One => answers.part1,
+            Part::Two => answers.part2,
+        }
+    }
+
+    pub(crate) fn solve(&self, lines: &[String], parts: &[Part]) -> Result<Answers> {
+        (self.solve)(lines, parts)
+    }
+}
+
+const DAYS: [Day; 12] = [
+    Day::new(1, solve::<Vec<day01::Rotation>>),
+    Day::new(2, solve::<day02::ProductIdRanges>),
+    Day::new(3, solve::<Vec<day03::BatteryBank>>),
+    Day::new(4, solve::<day04::FactoryFloor>),
+    Day::new(5, solve::<day05::IngredientDatabase>),
+    Day::new(6, solve::<day06::Worksheets>),
+    Day::new(7, solve::<day07::Manifold>),
+    Day::new(8, solve::<day08::Playground>),
+    Day::new(9, solve::<day09::Floor>),
+    Day::new(10, solve::<Vec<day10::Machine>>),
+    Day::new(11, solve::<day11::DirectedGraph>),
+    Day::new(12, solve::<day12::TreeFarm>),
+];
+
+pub fn days() -> &'static [Day] {
+    &DAYS
+}
+
+pub fn day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+// a part that fails keeps its error, so the other part's answer isn't lost
+#[derive(Debug)]
+pub struct Answers {
+    part1: Result<Option<String>>,
+    part2: Result<Option<String>>,
+    timings: Timings,
+}
+
+// parts that weren't solved have no timing
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+impl Answers {
+    pub fn part1(&self) -> std::result::Result<Option<&str>, &Error> {
+        self.part1.as_ref().map(Option::as_deref)
+    }
+
+    pub fn part2(&self) -> std::result::Result<Option<&str>, &Error> {
+        self.part2.as_ref().map(Option::as_deref)
+    }
+
+    pub fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    // the first part's error, if either failed
+    pub(crate) fn checked(self) -> Result<Self> {
+        match (self.part1, self.part2) {
+            (Err(e), _) | (_, Err(e)) => Err(e),
+            (part1, part2) => Ok(Self { part1, part2, timings: self.timings }),
+        }
+    }
+}
+
+impl Default for Answers {
+    fn default() -> Self {
+        Self { part1: Ok(None), part2: Ok(None), timings: Timings::default() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    All,
+    Days(RangeInclusive<u8>),
+}
+
+impl Selection {
+    pub fn days(&self) -> Vec<&'static Day> {
+        days()
+            .iter()
+            .filter(|day| self.includes(day.number))
+            .collect()
+    }
+
+    fn includes(&self, number: u8) -> bool {
+        match self {
+            Selection::All => true,
+            Selection::Days(range) => range.contains(&number),
+        }
+    }
+
+    fn parse_day(s: &str) -> Result<u8> {
+        Self::registered(Self::parse_number(s)?)
+    }
+
+    fn parse_number(s: &str) -> Result<u8> {
+        s.trim()
+            .parse::<u8>()
+            .map_err(|e| Error::validation(format!("Invalid day '{}': {}", s, e)))
+    }
+
+    fn registered(number: u8) -> Result<u8> {
+        if day(number).is_none() {
+            return Err(Error::validation(format!("No solver registered for day {}", number)));
+        }
+
+        Ok(number)
+    }
+}
+
+impl TryFrom<&str> for Selection {
+    type Error = Error;
+
+    // "all", "7", "3..9" (exclusive) or "3..=9" (inclusive); an exclusive end only has to be
+    // one past a registered day, so "1..13" is every day
+    fn try_from(value: &str) -> Result<Self> {
+        if value == "all" {
+            return Ok(Selection::All);
+        }
+
+        if let Some((start, end)) = value.split_once("..=") {
+            return Ok(Selection::Days(Self::parse_day(start)?..=Self::parse_day(end)?));
+        }
+
+        if let Some((start, end)) = value.split_once("..") {
+            let start = Self::parse_day(start)?;
+            let end = Self::parse_number(end)?;
+
+            if end <= start {
+                return Err(Error::validation(format!("Empty day range: {}", value)));
+            }
+
+            return Ok(Selection::Days(start..=Self::registered(end - 1)?));
+        }
+
+        let number = Self::parse_day(value)?;
+        Ok(Selection::Days(number..=number))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl TryFrom<&str> for Format {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(Error::validation(format!("Unknown format '{}'", value))),
+        }
+    }
+}
+
+pub struct Table {
+    rows: Vec<(u8, Result<Answers>)>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self { rows: Vec::new() }
+    }
+
+    pub fn push(&mut self, day: u8, result: Result<Answers>) {
+        self.rows.push((day, result));
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    // headed "Day 07" when the whole day failed, or "Day 07 part 2" when only a part did
+    pub fn errors(&self) -> impl Iterator<Item = (String, &Error)> {
+        self.rows.iter().flat_map(|(day, result)| match result {
+            Err(e) => vec![(format!("Day {:02}", day), e)],
+            Ok(answers) => [(1, answers.part1()), (2, answers.part2())]
+                .into_iter()
+                .filter_map(|(part, answer)| answer.err().map(|e| (format!("Day {:02} part {}", day, part), e)))
+                .collect(),
+        })
+    }
+
+    // one {day, part, answer, elapsed_ms} object per line, with `error` in place of `answer`
+    // for a failed part; days that fail to parse only appear in `errors`
+    pub fn json_lines(&self) -> String {
+        self.rows
+            .iter()
+            .filter_map(|(day, result)| result.as_ref().ok().map(|answers| (day, answers)))
+            .flat_map(|(day, answers)| {
+                let timings = answers.timings();
+
+                [
+                    (1, answers.part1(), timings.part1),
+                    (2, answers.part2(), timings.part2),
+                ]
+                .map(|(part, answer, elapsed)| {
+                    let answer = match answer {
+                        Ok(answer) => format!("\"answer\":{}", answer.map_or("null".to_string(), json_string)),
+                        Err(e) => format!("\"error\":{}", json_string(&e.to_string())),
+                    };
+
+                    format!(
+                        "{{\"day\":{},\"part\":{},{},\"elapsed_ms\":{}}}\n",
+                        day,
+                        part,
+                        answer,
+                        elapsed.map_or("null".to_string(), |e| format!("{:.3}", e.as_secs_f64() * 1000.0)),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn cells(&self) -> Vec<[String; 3]> {
+        let cell = |answer: std::result::Result<Option<&str>, &Error>| match answer {
+            Ok(answer) => answer.unwrap_or("-").to_string(),
+            Err(e) => Self::summary(e),
+        };
+
+        self.rows
+            .iter()
+            .map(|(day, result)| match result {
+                Ok(answers) => [format!("{:02}", day), cell(answers.part1()), cell(answers.part2())],
+                Err(e) => [format!("{:02}", day), Self::summary(e), String::new()],
+            })
+            .collect()
+    }
+
+    fn summary(error: &Error) -> String {
+        let first_line = error.to_string().lines().next().unwrap_or_default().to_string();
+
+        if first_line.starts_with("error: ") {
+            return first_line;
+        }
+
+        format!("error: {}", first_line)
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_columns(f, ["Day", "Part 1", "Part 2"], &self.cells())?;
+
+        for (heading, e) in self.errors() {
+            writeln!(f, "\n{}:\n{}", heading, e)?;
+        }
+
+        Ok(())
+    }
+}
+
+// answers are strings since they don't all fit in a JSON number
+fn json_string(value: &str) -> String {
+    let mut result = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+// left-aligned columns under a header row, separated by " | "
+pub(crate) fn write_columns<const N: usize>(
+    f: &mut fmt::Formatter<'_>,
+    header: [&str; N],
+    rows: &[[String; N]],
+) -> fmt::Result {
+    let header = header.map(str::to_string);
+
+    let widths: Vec<usize> = (0..N)
+        .map(|column| {
+            std::iter::once(&header)
+                .chain(rows.iter())
+                .map(|row| row[column].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |row: &[String; N]| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .join(" | ")
+    };
+
+    writeln!(f, "{}", format_row(&header).trim_end())?;
+    writeln!(f, "{}", widths.iter().map(|&width| "-".repeat(width)).join("-+-"))?;
+
+    for row in rows.iter() {
+        writeln!(f, "{}", format_row(row).trim_end())?;
+    }
+
+    Ok(())
+}
+
+fn solve<S: Solution>(input: &[String], parts: &[Part]) -> Result<Answers> {
+    let start = Instant::now();
+    let solution = S::parse(input)?;
+    let mut answers = Answers::default();
+    answers.timings.parse = start.elapsed();
+
+    for part in parts {
+        let start = Instant::now();
+
+        match part {
+            Part::One => {
+                answers.part1 = solution.part1().map(|answer| answer.render());
+                answers.timings.part1 = Some(start.elapsed());
+            }
+            Part::Two => {
+                answers.part2 = solution.part2().map(|answer| answer.render());
+                answers.timings.part2 = Some(start.elapsed());
+            }
+        }
+    }
+
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_selections() {
+        assert_eq!(Selection::try_from("all").unwrap(), Selection::All);
+        assert_eq!(Selection::try_from("7").unwrap(), Selection::Days(7..=7));
+        assert_eq!(Selection::try_from("3..9").unwrap(), Selection::Days(3..=8));
+        assert_eq!(Selection::try_from("3..=9").unwrap(), Selection::Days(3..=9));
+        assert_eq!(Selection::try_from("1..13").unwrap(), Selection::Days(1..=12));
+
+        assert!(Selection::try_from("13").is_err());
+        assert!(Selection::try_from("9..3").is_err());
+        assert!(Selection::try_from("1..14").is_err());
+        assert!(Selection::try_from("seven").is_err());
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let mut table = Table::new();
+
+        let answers = Answers {
+            part1: Ok(None),
+            part2: Ok(Some("4\"2".to_string())),
+            timings: Timings {
+                parse: Duration::from_millis(1),
+                part1: Some(Duration::from_micros(1500)),
+                part2: None,
+            },
+        };
+
+        table.push(2, Ok(answers));
+        table.push(3, Err(Error::validation("bad input")));
+
+        let answers = Answers {
+            part1: Ok(Some("5".to_string())),
+            part2: Err(Error::solver("no path")),
+            ..Answers::default()
+        };
+
+        table.push(4, Ok(answers));
+
+        assert_eq!(
+            table.json_lines(),
+            "{\"day\":2,\"part\":1,\"answer\":null,\"elapsed_ms\":1.500}\n\
+             {\"day\":2,\"part\":2,\"answer\":\"4\\\"2\",\"elapsed_ms\":null}\n\
+             {\"day\":4,\"part\":1,\"answer\":\"5\",\"elapsed_ms\":null}\n\
+             {\"day\":4,\"part\":2,\"error\":\"solver error: no path\",\"elapsed_ms\":null}\n"
+        );
+
+        let headings: Vec<String> = table.errors().map(|(heading, _)| heading).collect();
+        assert_eq!(headings, vec!["Day 03", "Day 04 part 2"]);
+    }
+
+    #[test]
+    fn registers_every_day_once() {
+        let numbers: Vec<u8> = days().iter().map(|day| day.number()).collect();
+        assert_eq!(numbers, (1..=12).collect::<Vec<u8>>());
+    }
+}
@@ -0,0 +1,38 @@
+use crate::Result;
+
+pub trait Solution: Sized {
+    type Part1: Answer;
+    type Part2: Answer;
+
+    fn parse(input: &[String]) -> Result<Self>;
+    fn part1(&self) -> Result<Self::Part1>;
+    fn part2(&self) -> Result<Self::Part2>;
+}
+
+pub trait Answer {
+    fn render(&self) -> Option<String>;
+}
+
+macro_rules! impl_answer {
+    ($($t:ty),*) => {
+        $(
+            impl Answer for $t {
+                fn render(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer!(u16, u32, u64, u128, usize, i32, i64);
+
+// for puzzles (or parts of puzzles) that have no answer to compute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoAnswer;
+
+impl Answer for NoAnswer {
+    fn render(&self) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use advent_of_code_2025::runner::{self, Part};
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+// one line of an answers file: "<day> <part> <input> <answer>", input relative to the file
+struct Expectation {
+    day: u8,
+    part: Part,
+    input: PathBuf,
+    answer: String,
+}
+
+impl Expectation {
+    fn parse_all(path: &Path) -> Vec<Expectation> {
+        let contents = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+
+        let directory = path.parent().unwrap();
+
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| {
+                Self::parse(directory, line).unwrap_or_else(|| {
+                    panic!("{}:{}: invalid expectation '{}'", path.display(), index + 1, line)
+                })
+            })
+            .collect()
+    }
+
+    fn parse(directory: &Path, line: &str) -> Option<Expectation> {
+        let [day, part, input, answer] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            return None;
+        };
+
+        let part = match part {
+            "1" => Part::One,
+            "2" => Part::Two,
+            _ => return None,
+        };
+
+        Some(Expectation {
+            day: day.parse().ok()?,
+            part,
+            input: directory.join(input),
+            answer: answer.to_string(),
+        })
+    }
+
+    // describes the drift, if any
+    fn check(&self) -> Option<String> {
+        let day = runner::day(self.day)
+            .unwrap_or_else(|| panic!("No solver registered for day {}", self.day));
+
+        let actual = match day.run_part(self.part, self.input.to_str()) {
+            Ok(Some(answer)) if answer == self.answer => return None,
+            Ok(Some(answer)) => answer,
+            Ok(None) => "no answer".to_string(),
+            Err(e) => format!("error: {}", e.to_string().lines().next().unwrap_or_default()),
+        };
+
+        Some(format!(
+            "day {:02} part {} ({})\n  - expected: {}\n  + actual:   {}",
+            self.day,
+            match self.part {
+                Part::One => 1,
+                Part::Two => 2,
+            },
+            self.input.strip_prefix(MANIFEST_DIR).unwrap_or(&self.input).display(),
+            self.answer,
+            actual,
+        ))
+    }
+}
+
+fn assert_answers(path: &Path) {
+    let drifts: Vec<String> = Expectation::parse_all(path)
+        .iter()
+        .filter_map(|expectation| expectation.check())
+        .collect();
+
+    assert!(
+        drifts.is_empty(),
+        "{} answer(s) drifted from {}:\n\n{}\n",
+        drifts.len(),
+        path.display(),
+        drifts.join("\n\n"),
+    );
+}
+
+#[test]
+fn examples_match_puzzle_answers() {
+    assert_answers(&Path::new(MANIFEST_DIR).join("tests/examples/answers.txt"));
+}
+
+// answers for the real inputs aren't committed; record them locally in input/answers.txt
+#[test]
+fn inputs_match_recorded_answers() {
+    let path = Path::new(MANIFEST_DIR).join("input/answers.txt");
+
+    if !path.exists() {
+        eprintln!("skipping: {} not found", path.display());
+        return;
+    }
+
+    assert_answers(&path);
+}